@@ -0,0 +1,84 @@
+use postgres::binary_copy::{BinaryCopyInWriter, BinaryCopyOutIter};
+use postgres::fallible_iterator::FallibleIterator;
+use postgres::{Client, NoTls};
+use postgres_types::{FromSql, ToSql};
+
+#[derive(Debug, ToSql, FromSql, PartialEq, Clone, Copy)]
+#[postgres(name = "mood")]
+enum Mood {
+    #[postgres(name = "sad")]
+    Sad,
+    #[postgres(name = "happy")]
+    Happy,
+}
+
+#[derive(Debug, ToSql, FromSql, PartialEq, Clone)]
+#[postgres(name = "address")]
+struct Address {
+    street: String,
+    number: Option<i32>,
+}
+
+#[test]
+fn enum_and_composite_round_trip() {
+    let mut conn = Client::connect("user=postgres host=localhost port=5433", NoTls).unwrap();
+    conn.batch_execute(
+        "CREATE TYPE pg_temp.mood AS ENUM ('sad', 'happy');
+         CREATE TYPE pg_temp.address AS (street TEXT, number INT);
+         CREATE TEMPORARY TABLE people (mood mood, address address, moods mood[]);",
+    )
+    .unwrap();
+
+    // the custom types are looked up from the server, just as when preparing a query
+    let stmt = conn
+        .prepare("SELECT mood, address, moods FROM people")
+        .unwrap();
+    let types = stmt
+        .columns()
+        .iter()
+        .map(|c| c.type_().clone())
+        .collect::<Vec<_>>();
+
+    let rows = vec![
+        (
+            Mood::Happy,
+            Address {
+                street: "Main St".to_string(),
+                number: Some(1),
+            },
+            vec![Mood::Happy, Mood::Sad],
+        ),
+        (
+            Mood::Sad,
+            Address {
+                street: "Elm St".to_string(),
+                number: None,
+            },
+            vec![],
+        ),
+    ];
+
+    let writer = conn
+        .copy_in("COPY people (mood, address, moods) FROM STDIN BINARY")
+        .unwrap();
+    let mut writer = BinaryCopyInWriter::new(writer, &types);
+    for (mood, address, moods) in &rows {
+        writer.write(&[mood, address, moods]).unwrap();
+    }
+    assert_eq!(writer.finish().unwrap(), 2);
+
+    let reader = conn
+        .copy_out("COPY people (mood, address, moods) TO STDOUT BINARY")
+        .unwrap();
+    let copied = BinaryCopyOutIter::new(reader, &types)
+        .map(|row| {
+            Ok((
+                row.get::<Mood>(0),
+                row.get::<Address>(1),
+                row.get::<Vec<Mood>>(2),
+            ))
+        })
+        .collect::<Vec<_>>()
+        .unwrap();
+    assert_eq!(copied, rows);
+}
@@ -0,0 +1,19 @@
+use postgres_types::{FromSql, ToSql};
+
+#[derive(FromSql, Debug)]
+#[postgres(deny_unknown_columns)]
+enum FromSqlEnum {
+    Foo,
+}
+
+#[derive(ToSql, Debug)]
+#[postgres(deny_unknown_columns)]
+struct ToSqlTupleStruct(i32, i32);
+
+#[derive(FromSql, Debug)]
+struct FieldAttr {
+    #[postgres(deny_unknown_columns)]
+    a: i32,
+}
+
+fn main() {}
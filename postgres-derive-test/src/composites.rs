@@ -227,6 +227,47 @@ fn missing_field() {
     assert!(err.source().unwrap().is::<WrongType>());
 }
 
+#[test]
+fn unknown_columns() {
+    #[derive(FromSql, Debug, PartialEq)]
+    #[postgres(name = "inventory_item")]
+    struct InventoryItem {
+        supplier_id: i32,
+        name: String,
+    }
+
+    #[derive(FromSql, Debug, PartialEq)]
+    #[postgres(name = "inventory_item", deny_unknown_columns)]
+    struct StrictInventoryItem {
+        supplier_id: i32,
+        name: String,
+    }
+
+    let mut conn = Client::connect("user=postgres host=localhost port=5433", NoTls).unwrap();
+    conn.batch_execute(
+        "CREATE TYPE pg_temp.inventory_item AS (
+            name TEXT,
+            supplier_id INT,
+            price DOUBLE PRECISION
+        );",
+    )
+    .unwrap();
+
+    let row = conn
+        .query_one("SELECT ROW('foobar', 100, 15.50)::inventory_item", &[])
+        .unwrap();
+    assert_eq!(
+        row.get::<_, InventoryItem>(0),
+        InventoryItem {
+            supplier_id: 100,
+            name: "foobar".to_owned(),
+        }
+    );
+
+    let err = row.try_get::<_, StrictInventoryItem>(0).unwrap_err();
+    assert!(err.source().unwrap().is::<WrongType>());
+}
+
 #[test]
 fn wrong_type() {
     #[derive(FromSql, ToSql, Debug, PartialEq)]
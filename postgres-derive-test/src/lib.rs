@@ -4,6 +4,7 @@ use postgres::Client;
 use postgres_types::{FromSqlOwned, ToSql};
 use std::fmt;
 
+mod binary_copy;
 mod composites;
 mod domains;
 mod enums;
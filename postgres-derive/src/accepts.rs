@@ -66,13 +66,39 @@ pub fn enum_body(name: &str, variants: &[Variant], allow_mismatch: bool) -> Toke
     }
 }
 
-pub fn composite_body(name: &str, trait_: &str, fields: &[Field]) -> TokenStream {
+/// With `allow_unknown`, the Postgres type may have fields which aren't mapped to any of `fields`.
+pub fn composite_body(
+    name: &str,
+    trait_: &str,
+    fields: &[Field],
+    allow_unknown: bool,
+) -> TokenStream {
     let num_fields = fields.len();
     let trait_ = Ident::new(trait_, Span::call_site());
     let traits = iter::repeat(&trait_);
     let field_names = fields.iter().map(|f| &f.name);
     let field_types = fields.iter().map(|f| &f.type_);
 
+    if allow_unknown {
+        return quote! {
+            if type_.name() != #name {
+                return false;
+            }
+
+            match *type_.kind() {
+                ::postgres_types::Kind::Composite(ref fields) => {
+                    true #(
+                        && fields.iter().any(|f| {
+                            f.name() == #field_names
+                                && <#field_types as ::postgres_types::#traits>::accepts(f.type_())
+                        })
+                    )*
+                }
+                _ => false,
+            }
+        };
+    }
+
     quote! {
         if type_.name() != #name {
             return false;
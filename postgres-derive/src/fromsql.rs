@@ -24,6 +24,21 @@ pub fn expand_derive_fromsql(input: DeriveInput) -> Result<TokenStream, Error> {
         ));
     }
 
+    if overrides.deny_unknown_columns
+        && !matches!(
+            input.data,
+            Data::Struct(DataStruct {
+                fields: Fields::Named(_),
+                ..
+            })
+        )
+    {
+        return Err(Error::new_spanned(
+            &input,
+            "#[postgres(deny_unknown_columns)] may only be applied to structs with named fields",
+        ));
+    }
+
     let name = overrides
         .name
         .clone()
@@ -101,7 +116,12 @@ pub fn expand_derive_fromsql(input: DeriveInput) -> Result<TokenStream, Error> {
                 .map(|field| Field::parse(field, overrides.rename_all))
                 .collect::<Result<Vec<_>, _>>()?;
             (
-                accepts::composite_body(&name, "FromSql", &fields),
+                accepts::composite_body(
+                    &name,
+                    "FromSql",
+                    &fields,
+                    !overrides.deny_unknown_columns,
+                ),
                 composite_body(&input.ident, &fields),
             )
         }
@@ -221,7 +241,10 @@ fn composite_body(ident: &Ident, fields: &[Field]) -> TokenStream {
                             postgres_types::private::read_value(field.type_(), &mut buf)?);
                     }
                 )*
-                _ => unreachable!(),
+                _ => {
+                    postgres_types::private::read_value::<postgres_types::private::Ignored>(
+                        field.type_(), &mut buf)?;
+                }
             }
         }
 
@@ -8,6 +8,7 @@ pub struct Overrides {
     pub rename_all: Option<RenameRule>,
     pub transparent: bool,
     pub allow_mismatch: bool,
+    pub deny_unknown_columns: bool,
 }
 
 impl Overrides {
@@ -17,6 +18,7 @@ impl Overrides {
             rename_all: None,
             transparent: false,
             allow_mismatch: false,
+            deny_unknown_columns: false,
         };
 
         for attr in attrs {
@@ -92,6 +94,14 @@ impl Overrides {
                                 ));
                             }
                             overrides.allow_mismatch = true;
+                        } else if path.is_ident("deny_unknown_columns") {
+                            if !container_attr {
+                                return Err(Error::new_spanned(
+                                    path,
+                                    "deny_unknown_columns is a container attribute",
+                                ));
+                            }
+                            overrides.deny_unknown_columns = true;
                         } else {
                             return Err(Error::new_spanned(path, "unknown override"));
                         }
@@ -22,6 +22,21 @@ pub fn expand_derive_tosql(input: DeriveInput) -> Result<TokenStream, Error> {
         ));
     }
 
+    if overrides.deny_unknown_columns
+        && !matches!(
+            input.data,
+            Data::Struct(DataStruct {
+                fields: Fields::Named(_),
+                ..
+            })
+        )
+    {
+        return Err(Error::new_spanned(
+            &input,
+            "#[postgres(deny_unknown_columns)] may only be applied to structs with named fields",
+        ));
+    }
+
     let name = overrides
         .name
         .clone()
@@ -95,7 +110,7 @@ pub fn expand_derive_tosql(input: DeriveInput) -> Result<TokenStream, Error> {
                     .map(|field| Field::parse(field, overrides.rename_all))
                     .collect::<Result<Vec<_>, _>>()?;
                 (
-                    accepts::composite_body(&name, "ToSql", &fields),
+                    accepts::composite_body(&name, "ToSql", &fields, false),
                     composite_body(&fields),
                 )
             }
@@ -76,6 +76,9 @@ pub struct MakeTlsConnector(native_tls::TlsConnector);
 #[cfg(feature = "runtime")]
 impl MakeTlsConnector {
     /// Creates a new connector.
+    ///
+    /// To use `SslNegotiation::Direct`, the connector must be built with `request_alpns(&["postgresql"])`, which
+    /// requires the `alpn` feature of `native-tls`.
     pub fn new(connector: native_tls::TlsConnector) -> MakeTlsConnector {
         MakeTlsConnector(connector)
     }
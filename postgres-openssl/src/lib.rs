@@ -65,6 +65,8 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 use tokio::io::{AsyncRead, AsyncWrite, BufReader, ReadBuf};
 use tokio_openssl::SslStream;
+#[cfg(feature = "runtime")]
+use tokio_postgres::config::{SslMode, SslNegotiation};
 use tokio_postgres::tls;
 #[cfg(feature = "runtime")]
 use tokio_postgres::tls::MakeTlsConnect;
@@ -121,6 +123,36 @@ where
         (self.config)(&mut ssl, domain)?;
         Ok(TlsConnector::new(ssl, domain))
     }
+
+    /// Like `make_tls_connect`, except that the certificate isn't required to match the domain name with
+    /// `sslmode=verify-ca`.
+    fn make_tls_connect_with_mode(
+        &mut self,
+        domain: &str,
+        mode: SslMode,
+    ) -> Result<TlsConnector, ErrorStack> {
+        let mut connector = <Self as MakeTlsConnect<S>>::make_tls_connect(self, domain)?;
+        if mode == SslMode::VerifyCa {
+            connector.ssl.set_verify_hostname(false);
+        }
+        Ok(connector)
+    }
+
+    /// Like `make_tls_connect_with_mode`, except that the `postgresql` ALPN protocol is offered with
+    /// `sslnegotiation=direct`, as the server requires for direct TLS connections.
+    fn make_tls_connect_with_negotiation(
+        &mut self,
+        domain: &str,
+        mode: SslMode,
+        negotiation: SslNegotiation,
+    ) -> Result<TlsConnector, ErrorStack> {
+        let mut connector =
+            <Self as MakeTlsConnect<S>>::make_tls_connect_with_mode(self, domain, mode)?;
+        if negotiation == SslNegotiation::Direct {
+            connector.ssl.set_alpn_protos(b"\x0apostgresql")?;
+        }
+        Ok(connector)
+    }
 }
 
 /// A `TlsConnect` implementation using the `openssl` crate.
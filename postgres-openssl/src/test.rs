@@ -109,3 +109,31 @@ async fn runtime() {
     assert_eq!(rows.len(), 1);
     assert_eq!(rows[0].get::<_, i32>(0), 1);
 }
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn verify_ca() {
+    let mut builder = SslConnector::builder(SslMethod::tls()).unwrap();
+    builder.set_ca_file("../test/server.crt").unwrap();
+    let connector = MakeTlsConnector::new(builder.build());
+
+    // the certificate is for localhost, so only verify-ca accepts it when connecting to the address
+    tokio_postgres::connect(
+        "host=127.0.0.1 port=5433 user=postgres sslmode=verify-full",
+        connector.clone(),
+    )
+    .await
+    .err()
+    .unwrap();
+
+    let (client, connection) = tokio_postgres::connect(
+        "host=127.0.0.1 port=5433 user=postgres sslmode=verify-ca",
+        connector,
+    )
+    .await
+    .unwrap();
+    let connection = connection.map(|r| r.unwrap());
+    tokio::spawn(connection);
+
+    client.batch_execute("SELECT 1").await.unwrap();
+}
@@ -130,6 +130,7 @@ enum State {
 pub struct ScramSha256 {
     message: String,
     state: State,
+    min_iterations: u32,
 }
 
 impl ScramSha256 {
@@ -158,9 +159,18 @@ impl ScramSha256 {
                 password: normalize(password),
                 channel_binding,
             },
+            min_iterations: 0,
         }
     }
 
+    /// Sets the minimum iteration count the backend may request.
+    ///
+    /// A lower count weakens the key derivation, so `update()` will fail if the backend asks for
+    /// fewer iterations than this. Defaults to 0.
+    pub fn set_min_iterations(&mut self, min_iterations: u32) {
+        self.min_iterations = min_iterations;
+    }
+
     /// Returns the message which should be sent to the backend in an `SASLResponse` message.
     pub fn message(&self) -> &[u8] {
         if let State::Done = self.state {
@@ -192,6 +202,16 @@ impl ScramSha256 {
             return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid nonce"));
         }
 
+        if parsed.iteration_count < self.min_iterations {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "iteration count {} is below the minimum of {}",
+                    parsed.iteration_count, self.min_iterations
+                ),
+            ));
+        }
+
         let salt = match STANDARD.decode(parsed.salt) {
             Ok(salt) => salt,
             Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidInput, e)),
@@ -477,4 +497,22 @@ mod test {
 
         scram.finish(server_final.as_bytes()).unwrap();
     }
+
+    #[test]
+    fn min_iterations() {
+        let nonce = "9IZ2O01zb9IgiIZ1WJ/zgpJB";
+        let server_first =
+            "r=9IZ2O01zb9IgiIZ1WJ/zgpJBjx/oIRLs02gGSHcw1KEty3eY,s=fs3IXBy7U7+IvVjZ,i\
+             =4096";
+
+        let mut scram =
+            ScramSha256::new_inner(b"foobar", ChannelBinding::unsupported(), nonce.to_string());
+        scram.set_min_iterations(4097);
+        assert!(scram.update(server_first.as_bytes()).is_err());
+
+        let mut scram =
+            ScramSha256::new_inner(b"foobar", ChannelBinding::unsupported(), nonce.to_string());
+        scram.set_min_iterations(4096);
+        scram.update(server_first.as_bytes()).unwrap();
+    }
 }
@@ -4,6 +4,7 @@ use byteorder::{BigEndian, ByteOrder, ReadBytesExt};
 use bytes::{Bytes, BytesMut};
 use fallible_iterator::FallibleIterator;
 use memchr::memchr;
+use std::borrow::Cow;
 use std::cmp;
 use std::io::{self, Read};
 use std::ops::Range;
@@ -27,6 +28,7 @@ pub const COPY_OUT_RESPONSE_TAG: u8 = b'H';
 pub const COPY_BOTH_RESPONSE_TAG: u8 = b'W';
 pub const EMPTY_QUERY_RESPONSE_TAG: u8 = b'I';
 pub const BACKEND_KEY_DATA_TAG: u8 = b'K';
+pub const NEGOTIATE_PROTOCOL_VERSION_TAG: u8 = b'v';
 pub const NO_DATA_TAG: u8 = b'n';
 pub const NOTICE_RESPONSE_TAG: u8 = b'N';
 pub const AUTHENTICATION_TAG: u8 = b'R';
@@ -128,6 +130,7 @@ pub enum Message {
     DataRow(DataRowBody),
     EmptyQueryResponse,
     ErrorResponse(ErrorResponseBody),
+    NegotiateProtocolVersion(NegotiateProtocolVersionBody),
     NoData,
     NoticeResponse(NoticeResponseBody),
     NotificationResponse(NotificationResponseBody),
@@ -241,6 +244,22 @@ impl Message {
                     secret_key,
                 })
             }
+            NEGOTIATE_PROTOCOL_VERSION_TAG => {
+                let newest_minor_version = buf.read_i32::<BigEndian>()?;
+                let len = buf.read_i32::<BigEndian>()?;
+                if len < 0 {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "invalid protocol option count",
+                    ));
+                }
+                let storage = buf.read_all();
+                Message::NegotiateProtocolVersion(NegotiateProtocolVersionBody {
+                    newest_minor_version,
+                    storage,
+                    len: len as u32,
+                })
+            }
             NO_DATA_TAG => Message::NoData,
             NOTICE_RESPONSE_TAG => {
                 let storage = buf.read_all();
@@ -771,27 +790,99 @@ impl<'a> FallibleIterator for ErrorFields<'a> {
         }
 
         let value_end = find_null(self.buf, 0)?;
-        let value = get_str(&self.buf[..value_end])?;
+        let value_bytes = &self.buf[..value_end];
         self.buf = &self.buf[value_end + 1..];
 
-        Ok(Some(ErrorField { type_, value }))
+        Ok(Some(ErrorField {
+            type_,
+            value: String::from_utf8_lossy(value_bytes),
+            value_bytes,
+        }))
     }
 }
 
 pub struct ErrorField<'a> {
     type_: u8,
-    value: &'a str,
+    value: Cow<'a, str>,
+    value_bytes: &'a [u8],
 }
 
-impl ErrorField<'_> {
+impl<'a> ErrorField<'a> {
     #[inline]
     pub fn type_(&self) -> u8 {
         self.type_
     }
 
+    /// Returns the value of the field.
+    ///
+    /// Errors sent before the `client_encoding` parameter takes effect may be in the server's encoding rather than
+    /// UTF-8, in which case any invalid sequences are replaced with `U+FFFD REPLACEMENT CHARACTER`.
     #[inline]
     pub fn value(&self) -> &str {
-        self.value
+        &self.value
+    }
+
+    /// Returns the raw bytes of the value of the field.
+    #[inline]
+    pub fn value_bytes(&self) -> &'a [u8] {
+        self.value_bytes
+    }
+}
+
+pub struct NegotiateProtocolVersionBody {
+    newest_minor_version: i32,
+    storage: Bytes,
+    len: u32,
+}
+
+impl NegotiateProtocolVersionBody {
+    #[inline]
+    pub fn newest_minor_version(&self) -> i32 {
+        self.newest_minor_version
+    }
+
+    #[inline]
+    pub fn options(&self) -> ProtocolOptions<'_> {
+        ProtocolOptions {
+            buf: &self.storage,
+            remaining: self.len,
+        }
+    }
+}
+
+pub struct ProtocolOptions<'a> {
+    buf: &'a [u8],
+    remaining: u32,
+}
+
+impl<'a> FallibleIterator for ProtocolOptions<'a> {
+    type Item = &'a str;
+    type Error = io::Error;
+
+    #[inline]
+    fn next(&mut self) -> io::Result<Option<&'a str>> {
+        if self.remaining == 0 {
+            if self.buf.is_empty() {
+                return Ok(None);
+            } else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "invalid message length: protocol options are not drained",
+                ));
+            }
+        }
+
+        self.remaining -= 1;
+        let end = find_null(self.buf, 0)?;
+        let value = get_str(&self.buf[..end])?;
+        self.buf = &self.buf[end + 1..];
+        Ok(Some(value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining as usize;
+        (len, Some(len))
     }
 }
 
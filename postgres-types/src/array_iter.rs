@@ -0,0 +1,54 @@
+//! An adapter serializing an iterator as an array.
+
+use bytes::BytesMut;
+use std::error::Error;
+use std::fmt;
+
+use crate::{IsNull, Kind, ToSql, Type};
+
+/// Serializes the elements of an iterator as a Postgres array, without collecting them into a `Vec` first.
+///
+/// The array's length is written before its elements, so the iterator must report its exact length up front. It is
+/// cloned each time the value is serialized, so iterators which borrow from a collection are the best fit:
+///
+/// ```rust
+/// # use postgres_types::{ArrayIter, ToSql};
+/// let ids: Vec<i64> = (1..=1000).collect();
+/// // binds the ids shifted by 1000 for `WHERE id = ANY($1)`, without allocating a second `Vec`
+/// let param = ArrayIter(ids.iter().map(|id| id + 1000));
+/// # let _: &(dyn ToSql + Sync) = &param;
+/// ```
+///
+/// Slices, arrays and `Vec`s can be passed directly, and are serialized without any intermediate allocation already.
+#[derive(Clone, Copy)]
+pub struct ArrayIter<I>(pub I);
+
+impl<I> fmt::Debug for ArrayIter<I>
+where
+    I: ExactSizeIterator,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("ArrayIter")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
+
+impl<I> ToSql for ArrayIter<I>
+where
+    I: ExactSizeIterator + Clone,
+    I::Item: ToSql,
+{
+    fn to_sql(&self, ty: &Type, w: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        crate::array_to_sql(self.0.len(), self.0.clone(), ty, w)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *ty.kind() {
+            Kind::Array(ref member) => I::Item::accepts(member),
+            _ => false,
+        }
+    }
+
+    to_sql_checked!();
+}
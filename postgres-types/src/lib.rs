@@ -181,6 +181,27 @@
 //!    Meh,
 //! }
 //! ```
+//!
+//! ## Unknown Composite Fields
+//!
+//! By default the generated implementation of [`FromSql`] for structs ignores fields of the Postgres composite type
+//! which don't correspond to any of the struct's fields, so a struct keeps working when the type gains new fields.
+//! To instead reject such types, the `#[postgres(deny_unknown_columns)]` attribute can be used on the struct
+//! definition. The generated implementation of [`ToSql`] always requires an exact match, since the server needs a
+//! value for every field.
+//!
+//! ```rust
+//! # #[cfg(feature = "derive")]
+//! use postgres_types::FromSql;
+//!
+//! # #[cfg(feature = "derive")]
+//! #[derive(Debug, FromSql)]
+//! #[postgres(deny_unknown_columns)]
+//! struct InventoryItem {
+//!     name: String,
+//!     supplier_id: i32,
+//! }
+//! ```
 #![warn(clippy::all, rust_2018_idioms, missing_docs)]
 use fallible_iterator::FallibleIterator;
 use postgres_protocol::types::{self, ArrayDimension};
@@ -204,6 +225,12 @@ use crate::type_gen::{Inner, Other};
 #[doc(inline)]
 pub use postgres_protocol::Oid;
 
+#[doc(inline)]
+pub use array_iter::ArrayIter;
+#[doc(inline)]
+pub use money::Money;
+#[doc(inline)]
+pub use object_id::ObjectId;
 #[doc(inline)]
 pub use pg_lsn::PgLsn;
 
@@ -293,6 +320,9 @@ mod uuid_1;
 #[cfg(feature = "with-time-0_2")]
 extern crate time_02 as time;
 
+mod array_iter;
+mod money;
+mod object_id;
 mod pg_lsn;
 #[doc(hidden)]
 pub mod private;
@@ -463,7 +493,10 @@ impl WrongType {
 /// | `i16`                             | SMALLINT, SMALLSERIAL                         |
 /// | `i32`                             | INT, SERIAL                                   |
 /// | `u32`                             | OID                                           |
+/// | `ObjectId`                        | OID, REGCLASS, REGTYPE and the other REG*     |
+/// |                                   | alias types                                   |
 /// | `i64`                             | BIGINT, BIGSERIAL                             |
+/// | `Money`                           | MONEY                                         |
 /// | `f32`                             | REAL                                          |
 /// | `f64`                             | DOUBLE PRECISION                              |
 /// | `&str`/`String`                   | VARCHAR, CHAR(n), TEXT, CITEXT, NAME, UNKNOWN |
@@ -515,6 +548,9 @@ impl WrongType {
 ///
 /// `FromSql` is implemented for `Vec<T>`, `Box<[T]>` and `[T; N]` where `T`
 /// implements `FromSql`, and corresponds to one-dimensional Postgres arrays.
+/// Arrays are decoded from the binary format, in which each element is
+/// length-prefixed, so element types whose text format uses a delimiter other
+/// than a comma (such as `box`, which uses a semicolon) are handled correctly.
 ///
 /// **Note:** the impl for arrays only exist when the Cargo feature `array-impls`
 /// is enabled.
@@ -810,7 +846,10 @@ pub enum IsNull {
 /// | `i16`                             | SMALLINT, SMALLSERIAL                |
 /// | `i32`                             | INT, SERIAL                          |
 /// | `u32`                             | OID                                  |
+/// | `ObjectId`                        | OID, REGCLASS, REGTYPE and the other |
+/// |                                   | REG* alias types                     |
 /// | `i64`                             | BIGINT, BIGSERIAL                    |
+/// | `Money`                           | MONEY                                |
 /// | `f32`                             | REAL                                 |
 /// | `f64`                             | DOUBLE PRECISION                     |
 /// | `&str`/`String`                   | VARCHAR, CHAR(n), TEXT, CITEXT, NAME |
@@ -959,33 +998,7 @@ impl<T: ToSql> ToSql for Option<T> {
 
 impl<T: ToSql> ToSql for &[T] {
     fn to_sql(&self, ty: &Type, w: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
-        let member_type = match *ty.kind() {
-            Kind::Array(ref member) => member,
-            _ => panic!("expected array type"),
-        };
-
-        // Arrays are normally one indexed by default but oidvector and int2vector *require* zero indexing
-        let lower_bound = match *ty {
-            Type::OID_VECTOR | Type::INT2_VECTOR => 0,
-            _ => 1,
-        };
-
-        let dimension = ArrayDimension {
-            len: downcast(self.len())?,
-            lower_bound,
-        };
-
-        types::array_to_sql(
-            Some(dimension),
-            member_type.oid(),
-            self.iter(),
-            |e, w| match e.to_sql(member_type, w)? {
-                IsNull::No => Ok(postgres_protocol::IsNull::No),
-                IsNull::Yes => Ok(postgres_protocol::IsNull::Yes),
-            },
-            w,
-        )?;
-        Ok(IsNull::No)
+        array_to_sql(self.len(), self.iter(), ty, w)
     }
 
     fn accepts(ty: &Type) -> bool {
@@ -1221,6 +1234,50 @@ impl ToSql for IpAddr {
     to_sql_checked!();
 }
 
+/// Serializes `len` elements from `iter` as a one dimensional array of type `ty`.
+fn array_to_sql<I>(
+    len: usize,
+    iter: I,
+    ty: &Type,
+    w: &mut BytesMut,
+) -> Result<IsNull, Box<dyn Error + Sync + Send>>
+where
+    I: Iterator,
+    I::Item: ToSql,
+{
+    let member_type = match *ty.kind() {
+        Kind::Array(ref member) => member,
+        _ => panic!("expected array type"),
+    };
+
+    // Arrays are normally one indexed by default but oidvector and int2vector *require* zero indexing
+    let lower_bound = match *ty {
+        Type::OID_VECTOR | Type::INT2_VECTOR => 0,
+        _ => 1,
+    };
+
+    let dimension = ArrayDimension {
+        len: downcast(len)?,
+        lower_bound,
+    };
+
+    let mut count = 0;
+    types::array_to_sql(
+        Some(dimension),
+        member_type.oid(),
+        iter.inspect(|_| count += 1),
+        |e, w| match e.to_sql(member_type, w)? {
+            IsNull::No => Ok(postgres_protocol::IsNull::No),
+            IsNull::Yes => Ok(postgres_protocol::IsNull::Yes),
+        },
+        w,
+    )?;
+    if count != len {
+        return Err(format!("expected {} array elements but got {}", len, count).into());
+    }
+    Ok(IsNull::No)
+}
+
 fn downcast(len: usize) -> Result<i32, Box<dyn Error + Sync + Send>> {
     if len > i32::max_value() as usize {
         Err("value too large to transmit".into())
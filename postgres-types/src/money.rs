@@ -0,0 +1,47 @@
+//! A typed wrapper for the money type.
+
+use bytes::BytesMut;
+use postgres_protocol::types;
+use std::error::Error;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+/// Postgres `MONEY` type.
+///
+/// The value is the amount in the currency's smallest unit (cents, for example), which is how Postgres stores it. The
+/// number of fractional digits depends on the server's `lc_monetary` setting, so it is up to the application to scale
+/// the value for display.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Money(pub i64);
+
+impl From<i64> for Money {
+    fn from(v: i64) -> Self {
+        Money(v)
+    }
+}
+
+impl From<Money> for i64 {
+    fn from(v: Money) -> i64 {
+        v.0
+    }
+}
+
+impl<'a> FromSql<'a> for Money {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let v = types::int8_from_sql(raw)?;
+        Ok(Money(v))
+    }
+
+    accepts!(MONEY);
+}
+
+impl ToSql for Money {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::int8_to_sql(self.0, out);
+        Ok(IsNull::No)
+    }
+
+    accepts!(MONEY);
+
+    to_sql_checked!();
+}
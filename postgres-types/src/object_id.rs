@@ -0,0 +1,86 @@
+//! A typed wrapper for object identifiers.
+
+use bytes::BytesMut;
+use postgres_protocol::types;
+use std::error::Error;
+use std::fmt;
+
+use crate::{FromSql, IsNull, Oid, ToSql, Type};
+
+/// Postgres `OID` type, and the `REG*` alias types which share its representation.
+///
+/// Unlike a plain `u32`, this can't be mixed up with other integer columns. The alias types such as `REGCLASS` are
+/// transferred as the underlying identifier, so cast them to `TEXT` in the query to read the object's name instead.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ObjectId(Oid);
+
+impl ObjectId {
+    /// Returns the raw identifier.
+    pub fn get(self) -> Oid {
+        self.0
+    }
+}
+
+impl From<Oid> for ObjectId {
+    fn from(oid: Oid) -> Self {
+        ObjectId(oid)
+    }
+}
+
+impl From<ObjectId> for Oid {
+    fn from(oid: ObjectId) -> Oid {
+        oid.0
+    }
+}
+
+impl fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl<'a> FromSql<'a> for ObjectId {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let v = types::oid_from_sql(raw)?;
+        Ok(v.into())
+    }
+
+    accepts!(
+        OID,
+        REGPROC,
+        REGPROCEDURE,
+        REGOPER,
+        REGOPERATOR,
+        REGCLASS,
+        REGTYPE,
+        REGCONFIG,
+        REGDICTIONARY,
+        REGNAMESPACE,
+        REGROLE,
+        REGCOLLATION
+    );
+}
+
+impl ToSql for ObjectId {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::oid_to_sql(self.0, out);
+        Ok(IsNull::No)
+    }
+
+    accepts!(
+        OID,
+        REGPROC,
+        REGPROCEDURE,
+        REGOPER,
+        REGOPERATOR,
+        REGCLASS,
+        REGTYPE,
+        REGCONFIG,
+        REGDICTIONARY,
+        REGNAMESPACE,
+        REGROLE,
+        REGCOLLATION
+    );
+
+    to_sql_checked!();
+}
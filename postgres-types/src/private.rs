@@ -12,6 +12,23 @@ pub fn read_be_i32(buf: &mut &[u8]) -> Result<i32, Box<dyn Error + Sync + Send>>
     Ok(i32::from_be_bytes(bytes))
 }
 
+/// Skips over a value of any type.
+pub struct Ignored;
+
+impl<'a> FromSql<'a> for Ignored {
+    fn from_sql(_: &Type, _: &'a [u8]) -> Result<Ignored, Box<dyn Error + Sync + Send>> {
+        Ok(Ignored)
+    }
+
+    fn from_sql_null(_: &Type) -> Result<Ignored, Box<dyn Error + Sync + Send>> {
+        Ok(Ignored)
+    }
+
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+}
+
 pub fn read_value<'a, T>(
     type_: &Type,
     buf: &mut &'a [u8],
@@ -20,6 +20,10 @@ pub struct BinaryCopyInWriter<'a> {
 
 impl<'a> BinaryCopyInWriter<'a> {
     /// Creates a new writer which will write rows of the provided types.
+    ///
+    /// Values are encoded with the same `ToSql` implementations used for query parameters, so custom types such as
+    /// enums and composites are supported. The `Type`s of such columns must come from the server, for example from
+    /// the columns of a statement prepared against the table.
     pub fn new(writer: CopyInWriter<'a>, types: &[Type]) -> BinaryCopyInWriter<'a> {
         let stream = writer
             .sink
@@ -72,6 +76,10 @@ pub struct BinaryCopyOutIter<'a> {
 
 impl<'a> BinaryCopyOutIter<'a> {
     /// Creates a new iterator from a raw copy out reader and the types of the columns being returned.
+    ///
+    /// Values are decoded with the same `FromSql` implementations used for query results, so custom types such as
+    /// enums and composites are supported. The `Type`s of such columns must come from the server, for example from
+    /// the columns of a statement prepared against the table.
     pub fn new(reader: CopyOutReader<'a>, types: &[Type]) -> BinaryCopyOutIter<'a> {
         let stream = reader
             .stream
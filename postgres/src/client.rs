@@ -1,10 +1,14 @@
 use crate::connection::Connection;
 use crate::{
-    CancelToken, Config, CopyInWriter, CopyOutReader, Notifications, RowIter, Statement,
-    ToStatement, Transaction, TransactionBuilder,
+    CancelToken, Config, ConnectionInfo, CopyInBuilder, CopyInWriter, CopyOutBuilder,
+    CopyOutReader, Notifications, RowIter, SessionInfo, Statement, ToStatement, Transaction,
+    TransactionBuilder,
 };
+use bytes::Bytes;
+use futures_util::TryStreamExt;
 use std::task::Poll;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio_postgres::error::DbError;
 use tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
 use tokio_postgres::types::{BorrowToSql, ToSql, Type};
 use tokio_postgres::{Error, Row, SimpleQueryMessage, Socket};
@@ -115,6 +119,62 @@ impl Client {
         self.connection.block_on(self.client.query(query, params))
     }
 
+    /// Like `query`, but also returns the notices the server sent while executing the statement.
+    ///
+    /// Notices raised by the statement, for example with `RAISE NOTICE` in a function or trigger it invokes, are
+    /// returned in the order they were received. They are still passed to the notice callback as well.
+    pub fn query_with_notices<T>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Row>, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.connection
+            .block_on(self.client.query_with_notices(query, params))
+    }
+
+    /// Like `execute`, but also returns the notices the server sent while executing the statement.
+    ///
+    /// See `query_with_notices` for details.
+    pub fn execute_with_notices<T>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(u64, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.connection
+            .block_on(self.client.execute_with_notices(query, params))
+    }
+
+    /// Like `query`, but gives up on the query if it has not completed by `deadline`.
+    ///
+    /// The deadline covers preparing the statement, if a query string is passed, as well as executing it. If it passes,
+    /// a cancellation request is sent to the server using the provided TLS connector so that the backend stops working
+    /// on the request, and its remaining responses are drained so that the client can continue to be used. A timeout
+    /// error is returned in that case, unless the query completes before the cancellation takes effect. A statement
+    /// which is only prepared after the deadline is never executed.
+    ///
+    /// Sending the cancellation and waiting for it to take effect is given at most 5 more seconds, after which the
+    /// timeout error is returned regardless.
+    pub fn query_deadline<T, U>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+        deadline: Instant,
+        tls: U,
+    ) -> Result<Vec<Row>, Error>
+    where
+        T: ?Sized + ToStatement,
+        U: MakeTlsConnect<Socket>,
+    {
+        self.connection
+            .block_on(self.client.query_deadline(query, params, deadline, tls))
+    }
+
     /// Executes a statement which returns a single row, returning it.
     ///
     /// Returns an error if the query does not return exactly one row.
@@ -197,6 +257,10 @@ impl Client {
     /// It takes an iterator of parameters rather than a slice, and returns an iterator of rows rather than collecting
     /// them into an array.
     ///
+    /// Rows are read from the socket as the iterator is advanced rather than buffered up front, so memory use stays
+    /// bounded no matter how large the result set is. If the iterator is dropped before it is exhausted, the rest of
+    /// the response is read and discarded the next time the client is used, and the connection remains usable.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -316,6 +380,14 @@ impl Client {
             .block_on(self.client.prepare_typed(query, types))
     }
 
+    /// Prepares several statements at once, pipelining them to the server in a single round trip.
+    ///
+    /// If any query fails to prepare, none of the statements are returned and the index of the failing query is
+    /// available via [`Error::statement_index`].
+    pub fn prepare_batch(&mut self, queries: &[&str]) -> Result<Vec<Statement>, Error> {
+        self.connection.block_on(self.client.prepare_batch(queries))
+    }
+
     /// Executes a `COPY FROM STDIN` statement, returning the number of rows created.
     ///
     /// The `query` argument can either be a `Statement`, or a raw query string. The data in the provided reader is
@@ -375,6 +447,40 @@ impl Client {
         Ok(CopyOutReader::new(self.connection.as_ref(), stream))
     }
 
+    /// Returns a builder for a `COPY ... FROM STDIN` query into the specified table.
+    ///
+    /// The builder generates the query from a column list and format options, quoting all names and values.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use postgres::{Client, CopyFormat, NoTls};
+    /// use std::io::Write;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+    ///
+    /// let mut writer = client
+    ///     .build_copy_in("people")
+    ///     .columns(&["name", "age"])
+    ///     .format(CopyFormat::Csv)
+    ///     .start()?;
+    /// writer.write_all(b"alice,30\nbob,25\n")?;
+    /// writer.finish()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn build_copy_in(&mut self, table: &str) -> CopyInBuilder<'_> {
+        CopyInBuilder::new(self.connection.as_ref(), self.client.build_copy_in(table))
+    }
+
+    /// Returns a builder for a `COPY ... TO STDOUT` query from the specified table.
+    ///
+    /// The builder generates the query from a column list and format options, quoting all names and values.
+    pub fn build_copy_out(&mut self, table: &str) -> CopyOutBuilder<'_> {
+        CopyOutBuilder::new(self.connection.as_ref(), self.client.build_copy_out(table))
+    }
+
     /// Executes a sequence of SQL statements using the simple query protocol.
     ///
     /// Statements should be separated by semicolons. If an error occurs, execution of the sequence will stop at that
@@ -394,6 +500,25 @@ impl Client {
         self.connection.block_on(self.client.simple_query(query))
     }
 
+    /// Sends raw frontend protocol messages to the server, returning the raw backend messages sent in response.
+    ///
+    /// This is a low-level escape hatch for protocol features this crate does not model. `messages` must contain
+    /// complete, encoded messages, and the last must be the only one which completes a request: a `Sync`, `Query` or
+    /// `FunctionCall`. Terminate and `COPY` messages may not be sent. Messages which don't meet these requirements are
+    /// rejected without being sent. The responses are returned as-is, including any `ErrorResponse` and the final
+    /// `ReadyForQuery`.
+    ///
+    /// # Warning
+    ///
+    /// The server's state is trusted to the caller. Messages which leave the session in a state the client doesn't
+    /// expect - such as starting a `COPY` with a query, or leaving an unterminated transaction - will cause later
+    /// requests to fail or hang.
+    pub fn send_raw(&mut self, messages: Bytes) -> Result<Vec<Bytes>, Error> {
+        let client = &self.client;
+        self.connection
+            .block_on(async { client.send_raw(messages).await?.try_collect().await })
+    }
+
     /// Validates the connection by performing a simple no-op query.
     ///
     /// If the specified timeout is reached before the backend responds, an error will be returned.
@@ -530,6 +655,15 @@ impl Client {
         self.client.clear_type_cache();
     }
 
+    /// Adds type definitions to the client's type information cache.
+    ///
+    /// The client will use these definitions for the types' OIDs rather than loading them from the database. This
+    /// avoids the catalog queries entirely, which matters when running behind a transaction-mode connection pooler
+    /// where they may be answered by a different backend. Registered types are forgotten by `clear_type_cache`.
+    pub fn register_types(&self, types: &[Type]) {
+        self.client.register_types(types);
+    }
+
     /// Determines if the client's connection has already closed.
     ///
     /// If this returns `true`, the client is no longer usable.
@@ -537,6 +671,28 @@ impl Client {
         self.client.is_closed()
     }
 
+    /// Returns information about how the connection was established, such as the authentication method and whether
+    /// TLS is in use.
+    pub fn connection_info(&self) -> ConnectionInfo {
+        self.client.connection_info()
+    }
+
+    /// Fetches a snapshot of the session's state, such as the current user and database and whether the server is a
+    /// standby, in a single round trip.
+    ///
+    /// This is useful for diagnostics, for example to confirm which server `target_session_attrs` selected.
+    pub fn session_info(&mut self) -> Result<SessionInfo, Error> {
+        self.connection.block_on(self.client.session_info())
+    }
+
+    /// Returns the error the server reported before terminating the connection, if any.
+    ///
+    /// This is set when the server sends a `FATAL` or `PANIC` error outside of any query, for example when the
+    /// connection is terminated by an administrator or the server is shutting down.
+    pub fn close_reason(&self) -> Option<DbError> {
+        self.client.close_reason()
+    }
+
     /// Closes the client's connection to the server.
     ///
     /// This is equivalent to `Client`'s `Drop` implementation, except that it returns any error encountered to the
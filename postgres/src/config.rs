@@ -4,6 +4,7 @@ use crate::connection::Connection;
 use crate::Client;
 use log::info;
 use std::fmt;
+use std::io;
 use std::net::IpAddr;
 use std::path::Path;
 use std::str::FromStr;
@@ -12,7 +13,8 @@ use std::time::Duration;
 use tokio::runtime;
 #[doc(inline)]
 pub use tokio_postgres::config::{
-    ChannelBinding, Host, LoadBalanceHosts, SslMode, TargetSessionAttrs,
+    ChannelBinding, Host, LoadBalanceHosts, RetryPolicy, SockRef, SslMode, SslNegotiation,
+    TargetSessionAttrs,
 };
 use tokio_postgres::error::DbError;
 use tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
@@ -34,8 +36,10 @@ use tokio_postgres::{Error, Socket};
 /// * `dbname` - The name of the database to connect to. Defaults to the username.
 /// * `options` - Command line options used to configure the server.
 /// * `application_name` - Sets the `application_name` parameter on the server.
-/// * `sslmode` - Controls usage of TLS. If set to `disable`, TLS will not be used. If set to `prefer`, TLS will be used
-///     if available, but not used otherwise. If set to `require`, TLS will be forced to be used. Defaults to `prefer`.
+/// * `fallback_application_name` - Sets the `application_name` parameter if `application_name` is not set.
+/// * `default_transaction_read_only` - If set to `on`, `true`, `yes` or `1`, transactions are read-only by default.
+/// * `sslmode` - Controls usage of TLS, as one of `disable`, `allow`, `prefer`, `require`, `verify-ca`, or `verify-full`.
+///     See `SslMode` for their meanings. Defaults to `prefer`.
 /// * `host` - The host to connect to. On Unix platforms, if the host starts with a `/` character it is treated as the
 ///     path to the directory containing Unix domain sockets. Otherwise, it is treated as a hostname. Multiple hosts
 ///     can be specified, separated by commas. Each host will be tried in turn when connecting. Required if connecting
@@ -68,7 +72,7 @@ use tokio_postgres::{Error, Socket};
 /// * `keepalives_interval` - The time interval between TCP keepalive probes.
 ///     This option is ignored when connecting with Unix sockets.
 /// * `keepalives_retries` - The maximum number of TCP keepalive probes that will be sent before dropping a connection.
-///     This option is ignored when connecting with Unix sockets.
+///     This option is ignored when connecting with Unix sockets. Also accepted as `keepalives_count`, as in libpq.
 /// * `target_session_attrs` - Specifies requirements of the session. If set to `read-write`, the client will check that
 ///     the `transaction_read_write` session parameter is set to `on`. This can be used to connect to the primary server
 ///     in a database cluster as opposed to the secondary read-only mirrors. Defaults to `all`.
@@ -194,11 +198,24 @@ impl Config {
     }
 
     /// Sets command line options used to configure the server.
+    ///
+    /// The server splits the options into arguments at whitespace, so spaces and backslashes within an argument must be
+    /// escaped with a backslash, as in `-c application_name=my\ app`. Quotes have no special meaning. The `option`
+    /// method adds a runtime parameter with this escaping applied.
     pub fn options(&mut self, options: &str) -> &mut Config {
         self.config.options(options);
         self
     }
 
+    /// Adds a runtime parameter to be set on the server, such as `search_path`, to the command line options.
+    ///
+    /// This appends `-c name=value` to the options, escaping any whitespace or backslashes so that the server sees
+    /// the value exactly as given.
+    pub fn option(&mut self, name: &str, value: &str) -> &mut Config {
+        self.config.option(name, value);
+        self
+    }
+
     /// Gets the command line options used to configure the server, if the
     /// options have been set with the `options` method.
     pub fn get_options(&self) -> Option<&str> {
@@ -217,6 +234,40 @@ impl Config {
         self.config.get_application_name()
     }
 
+    /// Sets a default value for the `application_name` runtime parameter.
+    ///
+    /// It is only sent if `application_name` has not been set, which allows libraries to name their connections
+    /// without overriding a name configured by the user.
+    pub fn fallback_application_name(&mut self, fallback_application_name: &str) -> &mut Config {
+        self.config
+            .fallback_application_name(fallback_application_name);
+        self
+    }
+
+    /// Gets the value of the `fallback_application_name` runtime parameter, if it has
+    /// been set with the `fallback_application_name` method.
+    pub fn get_fallback_application_name(&self) -> Option<&str> {
+        self.config.get_fallback_application_name()
+    }
+
+    /// Sets the `default_transaction_read_only` runtime parameter when connecting.
+    ///
+    /// When enabled, statements which write to the database fail at the server unless the transaction is explicitly
+    /// made read-write, guarding against accidental writes through connections meant for reads. Defaults to `false`.
+    pub fn default_transaction_read_only(
+        &mut self,
+        default_transaction_read_only: bool,
+    ) -> &mut Config {
+        self.config
+            .default_transaction_read_only(default_transaction_read_only);
+        self
+    }
+
+    /// Reports whether transactions will be read-only by default.
+    pub fn get_default_transaction_read_only(&self) -> bool {
+        self.config.get_default_transaction_read_only()
+    }
+
     /// Sets the SSL configuration.
     ///
     /// Defaults to `prefer`.
@@ -230,6 +281,20 @@ impl Config {
         self.config.get_ssl_mode()
     }
 
+    /// Sets how TLS is negotiated with the server.
+    ///
+    /// With `SslNegotiation::Direct`, the TLS connector must offer the `postgresql` ALPN protocol, which the server
+    /// requires for direct connections. Defaults to `postgres`.
+    pub fn ssl_negotiation(&mut self, ssl_negotiation: SslNegotiation) -> &mut Config {
+        self.config.ssl_negotiation(ssl_negotiation);
+        self
+    }
+
+    /// Gets how TLS is negotiated with the server.
+    pub fn get_ssl_negotiation(&self) -> SslNegotiation {
+        self.config.get_ssl_negotiation()
+    }
+
     /// Adds a host to the configuration.
     ///
     /// Multiple hosts can be specified by calling this method multiple times, and each will be tried in order. On Unix
@@ -317,6 +382,35 @@ impl Config {
         self.config.get_tcp_user_timeout()
     }
 
+    /// Controls the use of `TCP_NODELAY`, which disables Nagle's algorithm.
+    ///
+    /// Disabling this can reduce the number of packets sent for bulk transfers at the cost of latency. This is ignored
+    /// for Unix domain socket connections. Defaults to `true`.
+    pub fn tcp_nodelay(&mut self, tcp_nodelay: bool) -> &mut Config {
+        self.config.tcp_nodelay(tcp_nodelay);
+        self
+    }
+
+    /// Reports whether `TCP_NODELAY` will be set on the connection.
+    pub fn get_tcp_nodelay(&self) -> bool {
+        self.config.get_tcp_nodelay()
+    }
+
+    /// Requires the server process to be running as the specified operating system user.
+    ///
+    /// After connecting to a Unix domain socket, the credentials of the process on the other end are checked, and the
+    /// connection fails if its user name does not match. This guards against another local process impersonating the
+    /// server. This is ignored for TCP connections.
+    pub fn require_peer(&mut self, require_peer: &str) -> &mut Config {
+        self.config.require_peer(require_peer);
+        self
+    }
+
+    /// Gets the required server user name, if one has been set with the `require_peer` method.
+    pub fn get_require_peer(&self) -> Option<&str> {
+        self.config.get_require_peer()
+    }
+
     /// Controls the use of TCP keepalive.
     ///
     /// This is ignored for Unix domain socket connections. Defaults to `true`.
@@ -401,6 +495,21 @@ impl Config {
         self.config.get_channel_binding()
     }
 
+    /// Sets the minimum SCRAM iteration count the server may request during authentication.
+    ///
+    /// A server, or an intermediary impersonating one, could otherwise weaken the password hash by requesting very few
+    /// iterations. Authentication fails if the server asks for fewer than this. Defaults to 4096, the server's
+    /// default.
+    pub fn scram_min_iterations(&mut self, scram_min_iterations: u32) -> &mut Config {
+        self.config.scram_min_iterations(scram_min_iterations);
+        self
+    }
+
+    /// Gets the minimum SCRAM iteration count.
+    pub fn get_scram_min_iterations(&self) -> u32 {
+        self.config.get_scram_min_iterations()
+    }
+
     /// Sets the host load balancing behavior.
     ///
     /// Defaults to `disable`.
@@ -414,6 +523,189 @@ impl Config {
         self.config.get_load_balance_hosts()
     }
 
+    /// Sets the maximum size in bytes of a single message accepted from the server.
+    ///
+    /// A message whose length prefix exceeds this limit is treated as a protocol error and closes the connection,
+    /// rather than buffering it. This guards against buggy or malicious servers. Defaults to no limit.
+    pub fn max_backend_message_size(&mut self, max_backend_message_size: usize) -> &mut Config {
+        self.config
+            .max_backend_message_size(max_backend_message_size);
+        self
+    }
+
+    /// Gets the maximum backend message size, if one has been set with the `max_backend_message_size` method.
+    pub fn get_max_backend_message_size(&self) -> Option<usize> {
+        self.config.get_max_backend_message_size()
+    }
+
+    /// Sets the initial capacity of the buffer which data read from the server is stored in.
+    ///
+    /// Larger buffers reduce the number of reads needed to stream large results or `COPY` data, while smaller buffers
+    /// save memory for many mostly idle connections. The buffer still grows to fit larger messages. Defaults to 8 KiB.
+    pub fn read_buffer_size(&mut self, read_buffer_size: usize) -> &mut Config {
+        self.config.read_buffer_size(read_buffer_size);
+        self
+    }
+
+    /// Gets the read buffer size, if one has been set with the `read_buffer_size` method.
+    pub fn get_read_buffer_size(&self) -> Option<usize> {
+        self.config.get_read_buffer_size()
+    }
+
+    /// Sets the number of bytes of outgoing requests which are buffered before they are written to the server.
+    ///
+    /// Larger buffers combine writes of many small messages, such as the rows of a `COPY`, into fewer system calls.
+    /// Requests are always written once there is nothing left to buffer, regardless of this size. Defaults to 8 KiB.
+    pub fn write_buffer_size(&mut self, write_buffer_size: usize) -> &mut Config {
+        self.config.write_buffer_size(write_buffer_size);
+        self
+    }
+
+    /// Gets the write buffer size, if one has been set with the `write_buffer_size` method.
+    pub fn get_write_buffer_size(&self) -> Option<usize> {
+        self.config.get_write_buffer_size()
+    }
+
+    /// Sets the maximum number of rows which are passed from the connection to a query's row stream at once.
+    ///
+    /// The connection stops reading from the server while a query has unconsumed rows waiting, so this bounds the
+    /// memory used by a large result with a slow consumer to a small multiple of this many rows. Lower limits use less
+    /// memory at the cost of more wakeups. A limit of 0 is treated as 1. Defaults to no limit, in which case rows are
+    /// passed on in batches of however many were read at once.
+    pub fn row_buffer_limit(&mut self, row_buffer_limit: usize) -> &mut Config {
+        self.config.row_buffer_limit(row_buffer_limit);
+        self
+    }
+
+    /// Gets the row buffer limit, if one has been set with the `row_buffer_limit` method.
+    pub fn get_row_buffer_limit(&self) -> Option<usize> {
+        self.config.get_row_buffer_limit()
+    }
+
+    /// Sets a prefix for the names of prepared statements.
+    ///
+    /// Statements are named like `s0`, `s1`, and so on by default. With a prefix of `myapp_` they are instead named
+    /// `myapp_s0`, `myapp_s1`, which makes them easier to identify in `pg_prepared_statements` and the server logs.
+    pub fn statement_name_prefix(&mut self, statement_name_prefix: &str) -> &mut Config {
+        self.config.statement_name_prefix(statement_name_prefix);
+        self
+    }
+
+    /// Gets the prepared statement name prefix, if one has been set with the `statement_name_prefix` method.
+    pub fn get_statement_name_prefix(&self) -> Option<&str> {
+        self.config.get_statement_name_prefix()
+    }
+
+    /// Enables compatibility with connection poolers such as PgBouncer in transaction pooling mode.
+    ///
+    /// Under transaction pooling, successive requests outside of a transaction may be sent to different server
+    /// backends, so state left on a backend by an earlier request can't be relied upon. In this mode:
+    ///
+    /// * Statements are not prepared on the server. Preparing a statement only describes it, and its query is parsed
+    ///   again in the same round trip as each execution. This costs an extra parse per execution, and the server
+    ///   can't reuse query plans.
+    /// * The types of parameters and columns are never looked up in the database catalog. Built-in types work as
+    ///   usual, but custom types such as enums and composites must be registered ahead of time with
+    ///   `Client::register_types`, or preparing a statement which uses them will fail.
+    ///
+    /// Defaults to `false`.
+    pub fn pgbouncer_mode(&mut self, pgbouncer_mode: bool) -> &mut Config {
+        self.config.pgbouncer_mode(pgbouncer_mode);
+        self
+    }
+
+    /// Reports whether pgbouncer compatibility mode is enabled.
+    pub fn get_pgbouncer_mode(&self) -> bool {
+        self.config.get_pgbouncer_mode()
+    }
+
+    /// Controls whether statements are prepared again when their result types change.
+    ///
+    /// If a table a prepared statement reads from is altered, for example by a migration adding a column, executing
+    /// the statement fails with a `FEATURE_NOT_SUPPORTED` error saying that the cached plan must not change result
+    /// type. When enabled, the statement is instead prepared again and the query retried once, as happens anyway when
+    /// the server reports the statement doesn't exist. As with that case, this isn't possible while a transaction is
+    /// in progress, since the failure aborts it.
+    ///
+    /// Rows returned after a statement is prepared again are described by its new columns, which may not match
+    /// `Statement::columns`.
+    ///
+    /// Defaults to `false`.
+    pub fn auto_reprepare(&mut self, auto_reprepare: bool) -> &mut Config {
+        self.config.auto_reprepare(auto_reprepare);
+        self
+    }
+
+    /// Reports whether statements are prepared again when their result types change.
+    pub fn get_auto_reprepare(&self) -> bool {
+        self.config.get_auto_reprepare()
+    }
+
+    /// Sets the maximum number of requests which may be queued or in flight on the connection at once.
+    ///
+    /// Once this many requests are awaiting their responses, methods making a new request wait for an earlier one to
+    /// complete before sending it, so a producer pipelining many requests can't queue them without limit. The cleanup
+    /// done when statements and portals are dropped doesn't wait. A value of 0 is treated as 1, which disables
+    /// pipelining. Defaults to 1024.
+    pub fn max_pipeline_depth(&mut self, max_pipeline_depth: usize) -> &mut Config {
+        self.config.max_pipeline_depth(max_pipeline_depth);
+        self
+    }
+
+    /// Gets the maximum number of queued and in-flight requests.
+    pub fn get_max_pipeline_depth(&self) -> usize {
+        self.config.get_max_pipeline_depth()
+    }
+
+    /// Sets how long the addresses a host name resolves to are cached for.
+    ///
+    /// When set, connections made within the TTL of a previous resolution of the same host and port reuse its
+    /// addresses rather than resolving the host again. The cached addresses are discarded as soon as one of them
+    /// can't be reached or times out. The cache is shared by clones of this `Config`. Defaults to no caching.
+    pub fn dns_cache_ttl(&mut self, dns_cache_ttl: Duration) -> &mut Config {
+        self.config.dns_cache_ttl(dns_cache_ttl);
+        self
+    }
+
+    /// Gets the DNS cache TTL, if one has been set with the `dns_cache_ttl` method.
+    pub fn get_dns_cache_ttl(&self) -> Option<Duration> {
+        self.config.get_dns_cache_ttl()
+    }
+
+    /// Sets how long the connection waits for the server to send anything while a response is outstanding.
+    ///
+    /// If nothing is received from the server within the timeout while a request is waiting on it, the `Connection`
+    /// fails with a timeout error, so that queries on a half-open connection fail rather than hanging forever. The
+    /// timeout restarts whenever a message is sent or received, and time spent waiting on the client, such as for the
+    /// rows of a `COPY ... FROM STDIN` or for a slow consumer of a query's rows, doesn't count towards it. It should be
+    /// longer than the longest running query, since a query doesn't produce any messages until it has results.
+    /// Defaults to no timeout.
+    pub fn socket_read_timeout(&mut self, socket_read_timeout: Duration) -> &mut Config {
+        self.config.socket_read_timeout(socket_read_timeout);
+        self
+    }
+
+    /// Gets the socket read timeout, if one has been set with the `socket_read_timeout` method.
+    pub fn get_socket_read_timeout(&self) -> Option<Duration> {
+        self.config.get_socket_read_timeout()
+    }
+
+    /// Sets a function which is called to configure each TCP socket once it has connected, before the startup
+    /// handshake.
+    ///
+    /// This allows setting socket options which have no dedicated configuration method, such as the send and receive
+    /// buffer sizes or the traffic class. It runs after the options this `Config` controls, such as keepalives, have
+    /// been applied, and is not called for Unix sockets. If it returns an error, connecting to that address fails.
+    ///
+    /// The socket is a [`socket2::SockRef`](SockRef), from version 0.5 of `socket2`.
+    pub fn configure_socket<F>(&mut self, configure_socket: F) -> &mut Config
+    where
+        F: Fn(SockRef<'_>) -> io::Result<()> + Send + Sync + 'static,
+    {
+        self.config.configure_socket(configure_socket);
+        self
+    }
+
     /// Sets the notice callback.
     ///
     /// This callback will be invoked with the contents of every
@@ -451,6 +743,28 @@ impl Config {
         let connection = Connection::new(runtime, connection, self.notice_callback.clone());
         Ok(Client::new(connection, client))
     }
+
+    /// Opens a connection to a PostgreSQL database, retrying transient failures according to `policy`.
+    ///
+    /// Failures which are not transient, such as authentication errors or an invalid configuration, are returned
+    /// immediately. Otherwise, the error from the last attempt is returned once the policy is exhausted.
+    pub fn connect_with_retry<T>(&self, tls: T, policy: RetryPolicy) -> Result<Client, Error>
+    where
+        T: MakeTlsConnect<Socket> + 'static + Send,
+        T::TlsConnect: Send,
+        T::Stream: Send,
+        <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+    {
+        let runtime = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap(); // FIXME don't unwrap
+
+        let (client, connection) = runtime.block_on(self.config.connect_with_retry(tls, policy))?;
+
+        let connection = Connection::new(runtime, connection, self.notice_callback.clone());
+        Ok(Client::new(connection, client))
+    }
 }
 
 impl FromStr for Config {
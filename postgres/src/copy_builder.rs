@@ -0,0 +1,151 @@
+use crate::binary_copy::{BinaryCopyInWriter, BinaryCopyOutIter};
+use crate::connection::ConnectionRef;
+use crate::types::Type;
+use crate::{CopyInWriter, CopyOutReader, Error};
+use tokio_postgres::CopyFormat;
+
+macro_rules! copy_option_methods {
+    () => {
+        /// Sets the schema containing the table.
+        ///
+        /// By default, the table is looked up using the session's `search_path`.
+        pub fn schema(mut self, schema: &str) -> Self {
+            self.builder = self.builder.schema(schema);
+            self
+        }
+
+        /// Sets the columns to copy, in the order their values appear in the data.
+        ///
+        /// By default, all of the table's columns are copied in the order they were defined.
+        pub fn columns(mut self, columns: &[&str]) -> Self {
+            self.builder = self.builder.columns(columns);
+            self
+        }
+
+        /// Sets the format of the data.
+        ///
+        /// Defaults to `CopyFormat::Text`.
+        pub fn format(mut self, format: CopyFormat) -> Self {
+            self.builder = self.builder.format(format);
+            self
+        }
+
+        /// Sets the character separating the columns of a row.
+        ///
+        /// Defaults to a tab in the text format, and a comma in the CSV format. Not allowed in the binary format.
+        pub fn delimiter(mut self, delimiter: char) -> Self {
+            self.builder = self.builder.delimiter(delimiter);
+            self
+        }
+
+        /// Sets whether the data starts with a header line containing the column names.
+        ///
+        /// Defaults to `false`. Only allowed in the CSV format, and the text format on Postgres 15 and newer.
+        pub fn header(mut self, header: bool) -> Self {
+            self.builder = self.builder.header(header);
+            self
+        }
+
+        /// Sets the string representing a null value.
+        ///
+        /// Defaults to `\N` in the text format and an unquoted empty string in the CSV format. Not allowed in the
+        /// binary format.
+        pub fn null(mut self, null: &str) -> Self {
+            self.builder = self.builder.null(null);
+            self
+        }
+
+        /// Sets the character used to quote values.
+        ///
+        /// Defaults to a double quote. Only allowed in the CSV format.
+        pub fn quote(mut self, quote: char) -> Self {
+            self.builder = self.builder.quote(quote);
+            self
+        }
+
+        /// Sets the character used to escape the quote character within quoted values.
+        ///
+        /// Defaults to the quote character. Only allowed in the CSV format.
+        pub fn escape(mut self, escape: char) -> Self {
+            self.builder = self.builder.escape(escape);
+            self
+        }
+    };
+}
+
+/// A builder for `COPY ... FROM STDIN` queries.
+///
+/// The table and column names are quoted as identifiers and the options are quoted as literals, so none of them are
+/// interpreted as SQL.
+pub struct CopyInBuilder<'a> {
+    connection: ConnectionRef<'a>,
+    builder: tokio_postgres::CopyInBuilder<'a>,
+}
+
+impl<'a> CopyInBuilder<'a> {
+    pub(crate) fn new(
+        connection: ConnectionRef<'a>,
+        builder: tokio_postgres::CopyInBuilder<'a>,
+    ) -> CopyInBuilder<'a> {
+        CopyInBuilder {
+            connection,
+            builder,
+        }
+    }
+
+    copy_option_methods!();
+
+    /// Starts the copy, returning a writer for the data in the configured format.
+    ///
+    /// The copy *must* be explicitly completed via the writer's `finish` method. If it is not, the copy will be
+    /// aborted.
+    pub fn start(mut self) -> Result<CopyInWriter<'a>, Error> {
+        let sink = self.connection.block_on(self.builder.start())?;
+        Ok(CopyInWriter::new(self.connection, sink))
+    }
+
+    /// Starts a binary format copy, returning a writer which encodes rows of values of the specified types.
+    ///
+    /// The format set with the `format` method is ignored.
+    pub fn start_binary(self, types: &[Type]) -> Result<BinaryCopyInWriter<'a>, Error> {
+        let writer = self.format(CopyFormat::Binary).start()?;
+        Ok(BinaryCopyInWriter::new(writer, types))
+    }
+}
+
+/// A builder for `COPY ... TO STDOUT` queries.
+///
+/// The table and column names are quoted as identifiers and the options are quoted as literals, so none of them are
+/// interpreted as SQL.
+pub struct CopyOutBuilder<'a> {
+    connection: ConnectionRef<'a>,
+    builder: tokio_postgres::CopyOutBuilder<'a>,
+}
+
+impl<'a> CopyOutBuilder<'a> {
+    pub(crate) fn new(
+        connection: ConnectionRef<'a>,
+        builder: tokio_postgres::CopyOutBuilder<'a>,
+    ) -> CopyOutBuilder<'a> {
+        CopyOutBuilder {
+            connection,
+            builder,
+        }
+    }
+
+    copy_option_methods!();
+
+    /// Starts the copy, returning a reader of the data in the configured format.
+    pub fn start(mut self) -> Result<CopyOutReader<'a>, Error> {
+        let stream = self.connection.block_on(self.builder.start())?;
+        Ok(CopyOutReader::new(self.connection, stream))
+    }
+
+    /// Starts a binary format copy, returning an iterator which decodes rows of values of the specified types.
+    ///
+    /// The format set with the `format` method is ignored.
+    pub fn start_binary(self, types: &[Type]) -> Result<BinaryCopyOutIter<'a>, Error> {
+        let reader = self.format(CopyFormat::Binary).start()?;
+        Ok(BinaryCopyOutIter::new(reader, types))
+    }
+}
@@ -1,8 +1,11 @@
 use crate::types::{BorrowToSql, ToSql, Type};
 use crate::{
-    Client, CopyInWriter, CopyOutReader, Error, Row, RowIter, SimpleQueryMessage, Statement,
-    ToStatement, Transaction,
+    CancelToken, Client, CopyInBuilder, CopyInWriter, CopyOutBuilder, CopyOutReader, Error, Row,
+    RowIter, SessionInfo, SimpleQueryMessage, Socket, Statement, ToStatement, Transaction,
 };
+use std::time::Instant;
+use tokio_postgres::error::DbError;
+use tokio_postgres::tls::MakeTlsConnect;
 
 mod private {
     pub trait Sealed {}
@@ -11,6 +14,9 @@ mod private {
 /// A trait allowing abstraction over connections and transactions.
 ///
 /// This trait is "sealed", and cannot be implemented outside of this crate.
+///
+/// `Client::build_transaction` is left out, since the options it sets, such as the isolation level, only apply to a top
+/// level transaction, while `transaction` on a `Transaction` creates a savepoint.
 pub trait GenericClient: private::Sealed {
     /// Like `Client::execute`.
     fn execute<T>(&mut self, query: &T, params: &[&(dyn ToSql + Sync)]) -> Result<u64, Error>
@@ -50,6 +56,39 @@ pub trait GenericClient: private::Sealed {
     /// Like `Client::prepare_typed`.
     fn prepare_typed(&mut self, query: &str, types: &[Type]) -> Result<Statement, Error>;
 
+    /// Like `Client::prepare_batch`.
+    fn prepare_batch(&mut self, queries: &[&str]) -> Result<Vec<Statement>, Error>;
+
+    /// Like `Client::query_with_notices`.
+    fn query_with_notices<T>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Row>, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement;
+
+    /// Like `Client::execute_with_notices`.
+    fn execute_with_notices<T>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(u64, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement;
+
+    /// Like `Client::query_deadline`.
+    fn query_deadline<T, U>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+        deadline: Instant,
+        tls: U,
+    ) -> Result<Vec<Row>, Error>
+    where
+        T: ?Sized + ToStatement,
+        U: MakeTlsConnect<Socket>;
+
     /// Like `Client::copy_in`.
     fn copy_in<T>(&mut self, query: &T) -> Result<CopyInWriter<'_>, Error>
     where
@@ -60,12 +99,24 @@ pub trait GenericClient: private::Sealed {
     where
         T: ?Sized + ToStatement;
 
+    /// Like `Client::build_copy_in`.
+    fn build_copy_in(&mut self, table: &str) -> CopyInBuilder<'_>;
+
+    /// Like `Client::build_copy_out`.
+    fn build_copy_out(&mut self, table: &str) -> CopyOutBuilder<'_>;
+
     /// Like `Client::simple_query`.
     fn simple_query(&mut self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error>;
 
     /// Like `Client::batch_execute`.
     fn batch_execute(&mut self, query: &str) -> Result<(), Error>;
 
+    /// Like `Client::session_info`.
+    fn session_info(&mut self) -> Result<SessionInfo, Error>;
+
+    /// Like `Client::cancel_token`.
+    fn cancel_token(&self) -> CancelToken;
+
     /// Like `Client::transaction`.
     fn transaction(&mut self) -> Result<Transaction<'_>, Error>;
 }
@@ -123,6 +174,46 @@ impl GenericClient for Client {
         self.prepare_typed(query, types)
     }
 
+    fn prepare_batch(&mut self, queries: &[&str]) -> Result<Vec<Statement>, Error> {
+        self.prepare_batch(queries)
+    }
+
+    fn query_with_notices<T>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Row>, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.query_with_notices(query, params)
+    }
+
+    fn execute_with_notices<T>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(u64, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.execute_with_notices(query, params)
+    }
+
+    fn query_deadline<T, U>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+        deadline: Instant,
+        tls: U,
+    ) -> Result<Vec<Row>, Error>
+    where
+        T: ?Sized + ToStatement,
+        U: MakeTlsConnect<Socket>,
+    {
+        self.query_deadline(query, params, deadline, tls)
+    }
+
     fn copy_in<T>(&mut self, query: &T) -> Result<CopyInWriter<'_>, Error>
     where
         T: ?Sized + ToStatement,
@@ -137,6 +228,14 @@ impl GenericClient for Client {
         self.copy_out(query)
     }
 
+    fn build_copy_in(&mut self, table: &str) -> CopyInBuilder<'_> {
+        self.build_copy_in(table)
+    }
+
+    fn build_copy_out(&mut self, table: &str) -> CopyOutBuilder<'_> {
+        self.build_copy_out(table)
+    }
+
     fn simple_query(&mut self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error> {
         self.simple_query(query)
     }
@@ -145,6 +244,14 @@ impl GenericClient for Client {
         self.batch_execute(query)
     }
 
+    fn session_info(&mut self) -> Result<SessionInfo, Error> {
+        self.session_info()
+    }
+
+    fn cancel_token(&self) -> CancelToken {
+        self.cancel_token()
+    }
+
     fn transaction(&mut self) -> Result<Transaction<'_>, Error> {
         self.transaction()
     }
@@ -203,6 +310,46 @@ impl GenericClient for Transaction<'_> {
         self.prepare_typed(query, types)
     }
 
+    fn prepare_batch(&mut self, queries: &[&str]) -> Result<Vec<Statement>, Error> {
+        self.prepare_batch(queries)
+    }
+
+    fn query_with_notices<T>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Row>, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.query_with_notices(query, params)
+    }
+
+    fn execute_with_notices<T>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(u64, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.execute_with_notices(query, params)
+    }
+
+    fn query_deadline<T, U>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+        deadline: Instant,
+        tls: U,
+    ) -> Result<Vec<Row>, Error>
+    where
+        T: ?Sized + ToStatement,
+        U: MakeTlsConnect<Socket>,
+    {
+        self.query_deadline(query, params, deadline, tls)
+    }
+
     fn copy_in<T>(&mut self, query: &T) -> Result<CopyInWriter<'_>, Error>
     where
         T: ?Sized + ToStatement,
@@ -217,6 +364,14 @@ impl GenericClient for Transaction<'_> {
         self.copy_out(query)
     }
 
+    fn build_copy_in(&mut self, table: &str) -> CopyInBuilder<'_> {
+        self.build_copy_in(table)
+    }
+
+    fn build_copy_out(&mut self, table: &str) -> CopyOutBuilder<'_> {
+        self.build_copy_out(table)
+    }
+
     fn simple_query(&mut self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error> {
         self.simple_query(query)
     }
@@ -225,6 +380,14 @@ impl GenericClient for Transaction<'_> {
         self.batch_execute(query)
     }
 
+    fn session_info(&mut self) -> Result<SessionInfo, Error> {
+        self.session_info()
+    }
+
+    fn cancel_token(&self) -> CancelToken {
+        self.cancel_token()
+    }
+
     fn transaction(&mut self) -> Result<Transaction<'_>, Error> {
         self.transaction()
     }
@@ -68,13 +68,15 @@
 
 pub use fallible_iterator;
 pub use tokio_postgres::{
-    error, row, tls, types, Column, IsolationLevel, Notification, Portal, SimpleQueryMessage,
-    Socket, Statement, ToStatement,
+    error, row, tls, types, AuthMethod, Column, ConnectionInfo, CopyFormat, IsolationLevel,
+    Notification, Portal, QueryTimings, SessionInfo, SimpleColumn, SimpleQueryMessage, Socket,
+    Statement, ToStatement, Transport,
 };
 
 pub use crate::cancel_token::CancelToken;
 pub use crate::client::*;
 pub use crate::config::Config;
+pub use crate::copy_builder::{CopyInBuilder, CopyOutBuilder};
 pub use crate::copy_in_writer::CopyInWriter;
 pub use crate::copy_out_reader::CopyOutReader;
 #[doc(no_inline)]
@@ -95,6 +97,7 @@ mod cancel_token;
 mod client;
 pub mod config;
 mod connection;
+mod copy_builder;
 mod copy_in_writer;
 mod copy_out_reader;
 mod generic_client;
@@ -2,9 +2,12 @@ use crate::connection::ConnectionRef;
 use fallible_iterator::FallibleIterator;
 use futures_util::StreamExt;
 use std::pin::Pin;
-use tokio_postgres::{Error, Row, RowStream};
+use tokio_postgres::{Error, QueryTimings, Row, RowStream};
 
 /// The iterator returned by `query_raw`.
+///
+/// Rows are fetched from the connection lazily, as the iterator is advanced. Dropping the iterator early is fine; the
+/// remaining rows are discarded the next time the client is used.
 pub struct RowIter<'a> {
     connection: ConnectionRef<'a>,
     it: Pin<Box<RowStream>>,
@@ -24,6 +27,18 @@ impl<'a> RowIter<'a> {
     pub fn rows_affected(&self) -> Option<u64> {
         self.it.rows_affected()
     }
+
+    /// Determines if the query was empty, for example if it consisted only of whitespace or comments.
+    ///
+    /// This function will return `false` until the iterator has been exhausted.
+    pub fn is_empty_query(&self) -> bool {
+        self.it.is_empty_query()
+    }
+
+    /// Returns timestamps recorded over the course of the query.
+    pub fn timings(&self) -> QueryTimings {
+        self.it.timings()
+    }
 }
 
 impl FallibleIterator for RowIter<'_> {
@@ -1,8 +1,9 @@
 use std::io::{Read, Write};
+use std::iter;
 use std::str::FromStr;
 use std::sync::mpsc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio_postgres::error::SqlState;
 use tokio_postgres::types::Type;
 use tokio_postgres::NoTls;
@@ -22,6 +23,49 @@ fn prepare() {
     assert_eq!(stmt.columns()[1].type_(), &Type::TEXT);
 }
 
+#[test]
+fn query_raw_streams() {
+    let mut client = Client::connect(
+        "host=localhost port=5433 user=postgres application_name=query_raw_streams",
+        NoTls,
+    )
+    .unwrap();
+    let mut observer = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    let mut it = client
+        .query_raw(
+            "SELECT repeat('x', 100) FROM generate_series(1, 50000)",
+            iter::empty::<i32>(),
+        )
+        .unwrap();
+    assert!(it.next().unwrap().is_some());
+
+    // the server should be stuck waiting for us to read the rest of the ~5MB response
+    let mut blocked = false;
+    for _ in 0..50 {
+        let row = observer
+            .query_opt(
+                "SELECT 1 FROM pg_stat_activity \
+                 WHERE application_name = 'query_raw_streams' AND wait_event = 'ClientWrite'",
+                &[],
+            )
+            .unwrap();
+        if row.is_some() {
+            blocked = true;
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    assert!(
+        blocked,
+        "the server was not waiting to write the rest of the rows after 5 seconds"
+    );
+
+    drop(it);
+    let row = client.query_one("SELECT 1::INT", &[]).unwrap();
+    assert_eq!(row.get::<_, i32>(0), 1);
+}
+
 #[test]
 fn query_prepared() {
     let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
@@ -252,6 +296,86 @@ fn copy_in() {
     assert_eq!(rows[1].get::<_, &str>(1), "timothy");
 }
 
+#[test]
+fn copy_builder() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query("CREATE TEMPORARY TABLE foo (id INT, name TEXT)")
+        .unwrap();
+
+    let mut writer = client
+        .build_copy_in("foo")
+        .columns(&["name", "id"])
+        .format(CopyFormat::Csv)
+        .start()
+        .unwrap();
+    writer.write_all(b"steven,1\n\"tim, othy\",2\n").unwrap();
+    writer.finish().unwrap();
+
+    let mut reader = client
+        .build_copy_out("foo")
+        .format(CopyFormat::Csv)
+        .header(true)
+        .start()
+        .unwrap();
+    let mut data = String::new();
+    reader.read_to_string(&mut data).unwrap();
+    drop(reader);
+    assert_eq!(data, "id,name\n1,steven\n2,\"tim, othy\"\n");
+
+    let mut it = client
+        .build_copy_out("foo")
+        .columns(&["id"])
+        .start_binary(&[Type::INT4])
+        .unwrap();
+    let mut ids = vec![];
+    while let Some(row) = it.next().unwrap() {
+        ids.push(row.get::<i32>(0));
+    }
+    assert_eq!(ids, [1, 2]);
+}
+
+#[test]
+fn generic_client() {
+    fn insert_and_copy_out<C: GenericClient>(client: &mut C) -> String {
+        let statements = client
+            .prepare_batch(&["INSERT INTO foo (name) VALUES ($1)"])
+            .unwrap();
+        let (rows, notices) = client
+            .execute_with_notices(&statements[0], &[&"steven"])
+            .unwrap();
+        assert_eq!(rows, 1);
+        assert!(notices.is_empty());
+
+        let info = client.session_info().unwrap();
+        assert_eq!(info.current_user(), "postgres");
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let rows = client
+            .query_deadline("SELECT 1", &[], deadline, NoTls)
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+
+        let mut reader = client.build_copy_out("foo").start().unwrap();
+        let mut data = String::new();
+        reader.read_to_string(&mut data).unwrap();
+        data
+    }
+
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query("CREATE TEMPORARY TABLE foo (name TEXT)")
+        .unwrap();
+
+    let mut transaction = client.transaction().unwrap();
+    assert_eq!(insert_and_copy_out(&mut transaction), "steven\n");
+    transaction.rollback().unwrap();
+
+    assert_eq!(insert_and_copy_out(&mut client), "steven\n");
+}
+
 #[test]
 fn copy_in_abort() {
     let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
@@ -1,7 +1,13 @@
 use crate::connection::ConnectionRef;
-use crate::{CancelToken, CopyInWriter, CopyOutReader, Portal, RowIter, Statement, ToStatement};
+use crate::{
+    CancelToken, CopyInBuilder, CopyInWriter, CopyOutBuilder, CopyOutReader, Portal, RowIter,
+    SessionInfo, Statement, ToStatement,
+};
+use std::time::Instant;
+use tokio_postgres::error::DbError;
+use tokio_postgres::tls::MakeTlsConnect;
 use tokio_postgres::types::{BorrowToSql, ToSql, Type};
-use tokio_postgres::{Error, Row, SimpleQueryMessage};
+use tokio_postgres::{Error, Row, SimpleQueryMessage, Socket};
 
 /// A representation of a PostgreSQL database transaction.
 ///
@@ -61,6 +67,12 @@ impl<'a> Transaction<'a> {
         )
     }
 
+    /// Like `Client::prepare_batch`.
+    pub fn prepare_batch(&mut self, queries: &[&str]) -> Result<Vec<Statement>, Error> {
+        self.connection
+            .block_on(self.transaction.as_ref().unwrap().prepare_batch(queries))
+    }
+
     /// Like `Client::execute`.
     pub fn execute<T>(&mut self, query: &T, params: &[&(dyn ToSql + Sync)]) -> Result<u64, Error>
     where
@@ -115,6 +127,61 @@ impl<'a> Transaction<'a> {
         Ok(RowIter::new(self.connection.as_ref(), stream))
     }
 
+    /// Like `Client::query_with_notices`.
+    pub fn query_with_notices<T>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Row>, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.connection.block_on(
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .query_with_notices(query, params),
+        )
+    }
+
+    /// Like `Client::execute_with_notices`.
+    pub fn execute_with_notices<T>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(u64, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.connection.block_on(
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .execute_with_notices(query, params),
+        )
+    }
+
+    /// Like `Client::query_deadline`.
+    pub fn query_deadline<T, U>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+        deadline: Instant,
+        tls: U,
+    ) -> Result<Vec<Row>, Error>
+    where
+        T: ?Sized + ToStatement,
+        U: MakeTlsConnect<Socket>,
+    {
+        self.connection.block_on(
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .client()
+                .query_deadline(query, params, deadline, tls),
+        )
+    }
+
     /// Binds parameters to a statement, creating a "portal".
     ///
     /// Portals can be used with the `query_portal` method to page through the results of a query without being forced
@@ -183,6 +250,22 @@ impl<'a> Transaction<'a> {
         Ok(CopyOutReader::new(self.connection.as_ref(), stream))
     }
 
+    /// Like `Client::build_copy_in`.
+    pub fn build_copy_in(&mut self, table: &str) -> CopyInBuilder<'_> {
+        CopyInBuilder::new(
+            self.connection.as_ref(),
+            self.transaction.as_ref().unwrap().build_copy_in(table),
+        )
+    }
+
+    /// Like `Client::build_copy_out`.
+    pub fn build_copy_out(&mut self, table: &str) -> CopyOutBuilder<'_> {
+        CopyOutBuilder::new(
+            self.connection.as_ref(),
+            self.transaction.as_ref().unwrap().build_copy_out(table),
+        )
+    }
+
     /// Like `Client::simple_query`.
     pub fn simple_query(&mut self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error> {
         self.connection
@@ -195,6 +278,12 @@ impl<'a> Transaction<'a> {
             .block_on(self.transaction.as_ref().unwrap().batch_execute(query))
     }
 
+    /// Like `Client::session_info`.
+    pub fn session_info(&mut self) -> Result<SessionInfo, Error> {
+        self.connection
+            .block_on(self.transaction.as_ref().unwrap().session_info())
+    }
+
     /// Like `Client::cancel_token`.
     pub fn cancel_token(&self) -> CancelToken {
         CancelToken::new(self.transaction.as_ref().unwrap().cancel_token())
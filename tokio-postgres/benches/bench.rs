@@ -1,9 +1,11 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use futures_channel::oneshot;
+use futures_util::{pin_mut, SinkExt, TryStreamExt};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::runtime::Runtime;
-use tokio_postgres::{Client, NoTls};
+use tokio_postgres::{Client, Config, NoTls};
 
 fn setup() -> (Client, Runtime) {
     let runtime = Runtime::new().unwrap();
@@ -54,5 +56,54 @@ fn query_prepared(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, query_prepared);
+fn copy_buffer_sizes(c: &mut Criterion) {
+    let data = (0..100_000)
+        .map(|i| format!("{}\tsome text for row {}\n", i, i))
+        .collect::<String>();
+    let data = Bytes::from(data);
+
+    let mut group = c.benchmark_group("copy_buffer_size");
+    for size in [8 * 1024, 64 * 1024, 256 * 1024] {
+        let runtime = Runtime::new().unwrap();
+        let mut config = "host=localhost port=5433 user=postgres"
+            .parse::<Config>()
+            .unwrap();
+        config.read_buffer_size(size).write_buffer_size(size);
+        let (client, conn) = runtime.block_on(config.connect(NoTls)).unwrap();
+        runtime.spawn(async { conn.await.unwrap() });
+        runtime
+            .block_on(client.batch_execute("CREATE TEMPORARY TABLE foo (id INT, name TEXT)"))
+            .unwrap();
+
+        group.bench_with_input(BenchmarkId::new("copy_in", size), &size, |b, _| {
+            b.iter(|| {
+                runtime.block_on(async {
+                    let sink = client.copy_in("COPY foo FROM STDIN").await.unwrap();
+                    pin_mut!(sink);
+                    // send the data in small chunks, as when writing one row at a time
+                    for chunk in data.chunks(64) {
+                        sink.feed(data.slice_ref(chunk)).await.unwrap();
+                    }
+                    sink.finish().await.unwrap();
+                    client.batch_execute("TRUNCATE foo").await.unwrap();
+                })
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("copy_out", size), &size, |b, _| {
+            b.iter(|| {
+                runtime.block_on(async {
+                    let stream = client
+                        .copy_out("COPY (SELECT i, repeat('x', 32) FROM generate_series(1, 100000) i) TO STDOUT")
+                        .await
+                        .unwrap();
+                    stream.try_for_each(|_| async { Ok(()) }).await.unwrap();
+                })
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, query_prepared, copy_buffer_sizes);
 criterion_main!(benches);
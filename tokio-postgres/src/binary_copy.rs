@@ -32,6 +32,10 @@ pin_project! {
 
 impl BinaryCopyInWriter {
     /// Creates a new writer which will write rows of the provided types to the provided sink.
+    ///
+    /// Values are encoded with the same `ToSql` implementations used for query parameters, so custom types such as
+    /// enums and composites are supported. The `Type`s of such columns must come from the server, for example from
+    /// the columns of a statement prepared against the table.
     pub fn new(sink: CopyInSink<Bytes>, types: &[Type]) -> BinaryCopyInWriter {
         let mut buf = BytesMut::new();
         buf.put_slice(MAGIC);
@@ -117,21 +121,29 @@ struct Header {
 
 pin_project! {
     /// A stream of rows deserialized from the PostgreSQL binary copy format.
+    ///
+    /// Rows are reassembled from the underlying copy data regardless of how it is split into `CopyData` messages.
     pub struct BinaryCopyOutStream {
         #[pin]
         stream: CopyOutStream,
         types: Arc<Vec<Type>>,
         header: Option<Header>,
+        buf: BytesMut,
     }
 }
 
 impl BinaryCopyOutStream {
     /// Creates a stream from a raw copy out stream and the types of the columns being returned.
+    ///
+    /// Values are decoded with the same `FromSql` implementations used for query results, so custom types such as
+    /// enums and composites are supported. The `Type`s of such columns must come from the server, for example from
+    /// the columns of a statement prepared against the table.
     pub fn new(stream: CopyOutStream, types: &[Type]) -> BinaryCopyOutStream {
         BinaryCopyOutStream {
             stream,
             types: Arc::new(types.to_vec()),
             header: None,
+            buf: BytesMut::new(),
         }
     }
 }
@@ -140,87 +152,119 @@ impl Stream for BinaryCopyOutStream {
     type Item = Result<BinaryCopyOutRow, Error>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let this = self.project();
+        let mut this = self.project();
 
-        let chunk = match ready!(this.stream.poll_next(cx)) {
-            Some(Ok(chunk)) => chunk,
-            Some(Err(e)) => return Poll::Ready(Some(Err(e))),
-            None => return Poll::Ready(Some(Err(Error::closed()))),
-        };
-        let mut chunk = Cursor::new(chunk);
+        loop {
+            if this.header.is_none() {
+                *this.header = parse_header(this.buf)?;
+            }
+
+            if let Some(header) = this.header {
+                match parse_row(this.buf, header.has_oids, this.types)? {
+                    Some(Some(row)) => return Poll::Ready(Some(Ok(row))),
+                    Some(None) => return Poll::Ready(None),
+                    None => {}
+                }
+            }
 
-        let has_oids = match &this.header {
-            Some(header) => header.has_oids,
-            None => {
-                check_remaining(&chunk, HEADER_LEN)?;
-                if !chunk.chunk().starts_with(MAGIC) {
+            let chunk = match ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(Ok(chunk)) => chunk,
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None if this.buf.is_empty() => return Poll::Ready(Some(Err(Error::closed()))),
+                None => {
                     return Poll::Ready(Some(Err(Error::parse(io::Error::new(
-                        io::ErrorKind::InvalidData,
-                        "invalid magic value",
-                    )))));
+                        io::ErrorKind::UnexpectedEof,
+                        "unexpected EOF",
+                    )))))
                 }
-                chunk.advance(MAGIC.len());
+            };
 
-                let flags = chunk.get_i32();
-                let has_oids = (flags & (1 << 16)) != 0;
+            this.buf.extend_from_slice(&chunk);
+        }
+    }
+}
 
-                let header_extension = chunk.get_u32() as usize;
-                check_remaining(&chunk, header_extension)?;
-                chunk.advance(header_extension);
+/// Parses the file header, returning `None` if `buf` does not yet contain all of it.
+fn parse_header(buf: &mut BytesMut) -> Result<Option<Header>, Error> {
+    if buf.len() < HEADER_LEN {
+        return Ok(None);
+    }
 
-                *this.header = Some(Header { has_oids });
-                has_oids
-            }
-        };
+    let mut header = &buf[..];
+    if !header.starts_with(MAGIC) {
+        return Err(Error::parse(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid magic value",
+        )));
+    }
+    header.advance(MAGIC.len());
 
-        check_remaining(&chunk, 2)?;
-        let mut len = chunk.get_i16();
-        if len == -1 {
-            return Poll::Ready(None);
-        }
+    let flags = header.get_i32();
+    let has_oids = (flags & (1 << 16)) != 0;
 
-        if has_oids {
-            len += 1;
-        }
-        if len as usize != this.types.len() {
-            return Poll::Ready(Some(Err(Error::parse(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                format!("expected {} values but got {}", this.types.len(), len),
-            )))));
-        }
+    let header_extension = header.get_u32() as usize;
+    if header.remaining() < header_extension {
+        return Ok(None);
+    }
 
-        let mut ranges = vec![];
-        for _ in 0..len {
-            check_remaining(&chunk, 4)?;
-            let len = chunk.get_i32();
-            if len == -1 {
-                ranges.push(None);
-            } else {
-                let len = len as usize;
-                check_remaining(&chunk, len)?;
-                let start = chunk.position() as usize;
-                ranges.push(Some(start..start + len));
-                chunk.advance(len);
-            }
-        }
+    buf.advance(HEADER_LEN + header_extension);
+    Ok(Some(Header { has_oids }))
+}
+
+/// Parses a single row from the front of `buf`.
+///
+/// Returns `None` if `buf` does not yet contain a complete row, and `Some(None)` on the end-of-data trailer.
+fn parse_row(
+    buf: &mut BytesMut,
+    has_oids: bool,
+    types: &Arc<Vec<Type>>,
+) -> Result<Option<Option<BinaryCopyOutRow>>, Error> {
+    let mut chunk = Cursor::new(&buf[..]);
 
-        Poll::Ready(Some(Ok(BinaryCopyOutRow {
-            buf: chunk.into_inner(),
-            ranges,
-            types: this.types.clone(),
-        })))
+    if chunk.remaining() < 2 {
+        return Ok(None);
+    }
+    let mut len = chunk.get_i16();
+    if len == -1 {
+        buf.advance(2);
+        return Ok(Some(None));
     }
-}
 
-fn check_remaining(buf: &Cursor<Bytes>, len: usize) -> Result<(), Error> {
-    if buf.remaining() < len {
-        Err(Error::parse(io::Error::new(
-            io::ErrorKind::UnexpectedEof,
-            "unexpected EOF",
-        )))
-    } else {
-        Ok(())
+    if has_oids {
+        len += 1;
+    }
+    if len as usize != types.len() {
+        return Err(Error::parse(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("expected {} values but got {}", types.len(), len),
+        )));
     }
+
+    let mut ranges = vec![];
+    for _ in 0..len {
+        if chunk.remaining() < 4 {
+            return Ok(None);
+        }
+        let len = chunk.get_i32();
+        if len == -1 {
+            ranges.push(None);
+        } else {
+            let len = len as usize;
+            if chunk.remaining() < len {
+                return Ok(None);
+            }
+            let start = chunk.position() as usize;
+            ranges.push(Some(start..start + len));
+            chunk.advance(len);
+        }
+    }
+
+    let row_len = chunk.position() as usize;
+    Ok(Some(Some(BinaryCopyOutRow {
+        buf: buf.split_to(row_len).freeze(),
+        ranges,
+        types: types.clone(),
+    })))
 }
 
 /// A row of data parsed from a binary copy out stream.
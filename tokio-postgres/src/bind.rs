@@ -20,18 +20,25 @@ where
     I: IntoIterator<Item = P>,
     I::IntoIter: ExactSizeIterator,
 {
+    let statement = statement.current();
     let name = format!("p{}", NEXT_ID.fetch_add(1, Ordering::SeqCst));
     let buf = client.with_buf(|buf| {
+        query::encode_parse(&statement, buf)?;
         query::encode_bind(&statement, params, &name, buf)?;
         frontend::sync(buf);
         Ok(buf.split().freeze())
     })?;
 
-    let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+    let mut responses = client
+        .send(RequestMessages::Single(FrontendMessage::Raw(buf)))
+        .await?;
 
-    match responses.next().await? {
-        Message::BindComplete => {}
-        _ => return Err(Error::unexpected_message()),
+    loop {
+        match responses.next().await? {
+            Message::ParseComplete => {}
+            Message::BindComplete => break,
+            _ => return Err(Error::unexpected_message()),
+        }
     }
 
     Ok(Portal::new(client, name, statement))
@@ -1,5 +1,5 @@
 use crate::client::SocketConfig;
-use crate::config::SslMode;
+use crate::config::{SslMode, SslNegotiation};
 use crate::tls::MakeTlsConnect;
 use crate::{cancel_query_raw, connect_socket, Error, Socket};
 use std::io;
@@ -7,6 +7,7 @@ use std::io;
 pub(crate) async fn cancel_query<T>(
     config: Option<SocketConfig>,
     ssl_mode: SslMode,
+    ssl_negotiation: SslNegotiation,
     mut tls: T,
     process_id: i32,
     secret_key: i32,
@@ -25,7 +26,11 @@ where
     };
 
     let tls = tls
-        .make_tls_connect(config.hostname.as_deref().unwrap_or(""))
+        .make_tls_connect_with_negotiation(
+            config.hostname.as_deref().unwrap_or(""),
+            ssl_mode,
+            ssl_negotiation,
+        )
         .map_err(|e| Error::tls(e.into()))?;
     let has_hostname = config.hostname.is_some();
 
@@ -34,10 +39,23 @@ where
         config.port,
         config.connect_timeout,
         config.tcp_user_timeout,
+        config.tcp_nodelay,
+        config.require_peer.as_deref(),
         config.keepalive.as_ref(),
     )
     .await?;
+    if let Some(configurer) = &config.socket_configurer {
+        configurer.configure(&socket)?;
+    }
 
-    cancel_query_raw::cancel_query_raw(socket, ssl_mode, tls, has_hostname, process_id, secret_key)
-        .await
+    cancel_query_raw::cancel_query_raw(
+        socket,
+        ssl_mode,
+        ssl_negotiation,
+        tls,
+        has_hostname,
+        process_id,
+        secret_key,
+    )
+    .await
 }
@@ -1,11 +1,18 @@
 use crate::codec::{BackendMessages, FrontendMessage};
-use crate::config::SslMode;
-use crate::connection::{Request, RequestMessages};
+#[cfg(feature = "runtime")]
+use crate::config::SocketConfigurer;
+use crate::config::{Config, SslMode, SslNegotiation};
+use crate::connection::{NoticeSink, Request, RequestMessages};
+use crate::connection_info::ConnectionInfo;
 use crate::copy_both::CopyBothDuplex;
 use crate::copy_out::CopyOutStream;
+use crate::error::DbError;
+#[cfg(feature = "runtime")]
+use crate::error::SqlState;
 #[cfg(feature = "runtime")]
 use crate::keepalive::KeepaliveConfig;
 use crate::query::RowStream;
+use crate::session_info::{self, SessionInfo};
 use crate::simple_query::SimpleQueryStream;
 #[cfg(feature = "runtime")]
 use crate::tls::MakeTlsConnect;
@@ -14,11 +21,12 @@ use crate::types::{Oid, ToSql, Type};
 #[cfg(feature = "runtime")]
 use crate::Socket;
 use crate::{
-    copy_both, copy_in, copy_out, prepare, query, simple_query, slice_iter, CancelToken,
-    CopyInSink, Error, Row, SimpleQueryMessage, Statement, ToStatement, Transaction,
+    copy_both, copy_in, copy_out, prepare, query, raw_message, simple_query, slice_iter,
+    streaming_param, CancelToken, CopyInBuilder, CopyInSink, CopyOutBuilder, Error,
+    RawMessageStream, Row, SimpleQueryMessage, Statement, StreamingParam, ToStatement, Transaction,
     TransactionBuilder,
 };
-use bytes::{Buf, BytesMut};
+use bytes::{Buf, Bytes, BytesMut};
 use fallible_iterator::FallibleIterator;
 use futures_channel::mpsc;
 use futures_util::{future, pin_mut, ready, StreamExt, TryStreamExt};
@@ -26,7 +34,8 @@ use parking_lot::Mutex;
 use postgres_protocol::message::{backend::Message, frontend};
 use postgres_types::BorrowToSql;
 use std::collections::HashMap;
-use std::fmt;
+#[cfg(feature = "runtime")]
+use std::future::Future;
 #[cfg(feature = "runtime")]
 use std::net::IpAddr;
 #[cfg(feature = "runtime")]
@@ -34,12 +43,22 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 #[cfg(feature = "runtime")]
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use std::{fmt, mem};
 use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+#[cfg(feature = "runtime")]
+use tokio::time;
+
+/// How long `Client::query_deadline` waits for a cancellation to take effect once the deadline has passed.
+#[cfg(feature = "runtime")]
+const CANCEL_GRACE_PERIOD: Duration = Duration::from_secs(5);
 
 pub struct Responses {
     receiver: mpsc::Receiver<BackendMessages>,
     cur: BackendMessages,
+    close_reason: Arc<Mutex<Option<DbError>>>,
+    sequence: u64,
 }
 
 impl Responses {
@@ -53,7 +72,7 @@ impl Responses {
 
             match ready!(self.receiver.poll_next_unpin(cx)) {
                 Some(messages) => self.cur = messages,
-                None => return Poll::Ready(Err(Error::closed())),
+                None => return Poll::Ready(Err(closed(&self.close_reason))),
             }
         }
     }
@@ -61,6 +80,20 @@ impl Responses {
     pub async fn next(&mut self) -> Result<Message, Error> {
         future::poll_fn(|cx| self.poll_next(cx)).await
     }
+
+    /// Like `poll_next`, but returns each message unparsed, and doesn't convert error responses into errors.
+    pub fn poll_next_raw(&mut self, cx: &mut Context<'_>) -> Poll<Result<Bytes, Error>> {
+        loop {
+            if let Some(message) = self.cur.next_raw().map_err(Error::parse)? {
+                return Poll::Ready(Ok(message));
+            }
+
+            match ready!(self.receiver.poll_next_unpin(cx)) {
+                Some(messages) => self.cur = messages,
+                None => return Poll::Ready(Err(closed(&self.close_reason))),
+            }
+        }
+    }
 }
 
 /// A cache of type info and prepared statements for fetching type info
@@ -83,25 +116,103 @@ struct CachedTypeInfo {
     types: HashMap<Oid, Type>,
 }
 
+/// Returns the error reported when the connection is found to be closed, including the reason given by the server if
+/// there was one.
+fn closed(close_reason: &Mutex<Option<DbError>>) -> Error {
+    match &*close_reason.lock() {
+        Some(reason) => Error::closed_by_server(reason.clone()),
+        None => Error::closed(),
+    }
+}
+
 pub struct InnerClient {
     sender: mpsc::UnboundedSender<Request>,
     cached_typeinfo: Mutex<CachedTypeInfo>,
 
+    /// The fatal error sent by the server before it closed the connection, shared with the `Connection`.
+    close_reason: Arc<Mutex<Option<DbError>>>,
+
     /// A buffer to use when writing out postgres commands.
     buffer: Mutex<BytesMut>,
+
+    /// Prepended to the names of prepared statements.
+    statement_name_prefix: String,
+
+    /// Avoids server-side prepared statements and type lookups, for use behind a transaction-mode pooler.
+    pgbouncer_mode: bool,
+
+    /// Prepares statements again when their cached plans are invalidated by schema changes.
+    auto_reprepare: bool,
+
+    /// Limits the number of requests queued or in flight to `Config::max_pipeline_depth`.
+    pipeline_slots: Arc<Semaphore>,
+
+    /// The number of requests sent, locked while sending so it orders them the same way the connection receives them.
+    requests_sent: Mutex<u64>,
 }
 
 impl InnerClient {
-    pub fn send(&self, messages: RequestMessages) -> Result<Responses, Error> {
+    pub async fn send(&self, messages: RequestMessages) -> Result<Responses, Error> {
+        self.send_with_notices(messages, None).await
+    }
+
+    /// Sends a request, first waiting for room in the pipeline.
+    pub async fn send_with_notices(
+        &self,
+        messages: RequestMessages,
+        notices: Option<NoticeSink>,
+    ) -> Result<Responses, Error> {
+        let permit = self.pipeline_slot().await?;
+        self.send_request(messages, notices, Some(permit), false)
+    }
+
+    /// Sends a request without waiting for room in the pipeline.
+    ///
+    /// This is for requests made from synchronous code, like the cleanup done when statements and portals are dropped.
+    pub fn send_nowait(&self, messages: RequestMessages) -> Result<Responses, Error> {
+        self.send_request(messages, None, None, false)
+    }
+
+    /// Sends the messages of `Client::send_raw`, which close the connection if they start a `COPY`.
+    pub async fn send_raw(&self, messages: RequestMessages) -> Result<Responses, Error> {
+        let permit = self.pipeline_slot().await?;
+        self.send_request(messages, None, Some(permit), true)
+    }
+
+    async fn pipeline_slot(&self) -> Result<OwnedSemaphorePermit, Error> {
+        self.pipeline_slots
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| closed(&self.close_reason))
+    }
+
+    fn send_request(
+        &self,
+        messages: RequestMessages,
+        notices: Option<NoticeSink>,
+        permit: Option<OwnedSemaphorePermit>,
+        raw: bool,
+    ) -> Result<Responses, Error> {
         let (sender, receiver) = mpsc::channel(1);
-        let request = Request { messages, sender };
+        let request = Request {
+            messages,
+            sender,
+            notices,
+            permit,
+            raw,
+        };
+        let mut requests_sent = self.requests_sent.lock();
         self.sender
             .unbounded_send(request)
-            .map_err(|_| Error::closed())?;
+            .map_err(|_| closed(&self.close_reason))?;
+        *requests_sent += 1;
 
         Ok(Responses {
             receiver,
             cur: BackendMessages::empty(),
+            close_reason: self.close_reason.clone(),
+            sequence: *requests_sent,
         })
     }
 
@@ -141,6 +252,23 @@ impl InnerClient {
         self.cached_typeinfo.lock().types.clear();
     }
 
+    pub fn statement_name_prefix(&self) -> &str {
+        &self.statement_name_prefix
+    }
+
+    pub fn pgbouncer_mode(&self) -> bool {
+        self.pgbouncer_mode
+    }
+
+    pub fn auto_reprepare(&self) -> bool {
+        self.auto_reprepare
+    }
+
+    /// Determines if any requests were sent after the one `responses` belongs to.
+    pub fn sent_after(&self, responses: &Responses) -> bool {
+        *self.requests_sent.lock() != responses.sequence
+    }
+
     /// Call the given function with a buffer to be used when writing out
     /// postgres commands.
     pub fn with_buf<F, R>(&self, f: F) -> R
@@ -162,7 +290,10 @@ pub(crate) struct SocketConfig {
     pub port: u16,
     pub connect_timeout: Option<Duration>,
     pub tcp_user_timeout: Option<Duration>,
+    pub tcp_nodelay: bool,
+    pub require_peer: Option<String>,
     pub keepalive: Option<KeepaliveConfig>,
+    pub socket_configurer: Option<SocketConfigurer>,
 }
 
 #[cfg(feature = "runtime")]
@@ -182,28 +313,42 @@ pub struct Client {
     #[cfg(feature = "runtime")]
     socket_config: Option<SocketConfig>,
     ssl_mode: SslMode,
+    ssl_negotiation: SslNegotiation,
     process_id: i32,
     secret_key: i32,
+    connection_info: ConnectionInfo,
 }
 
 impl Client {
     pub(crate) fn new(
         sender: mpsc::UnboundedSender<Request>,
-        ssl_mode: SslMode,
+        config: &Config,
         process_id: i32,
         secret_key: i32,
+        connection_info: ConnectionInfo,
+        close_reason: Arc<Mutex<Option<DbError>>>,
     ) -> Client {
         Client {
             inner: Arc::new(InnerClient {
                 sender,
                 cached_typeinfo: Default::default(),
+                close_reason,
                 buffer: Default::default(),
+                statement_name_prefix: config.statement_name_prefix.clone().unwrap_or_default(),
+                pgbouncer_mode: config.pgbouncer_mode,
+                auto_reprepare: config.auto_reprepare,
+                pipeline_slots: Arc::new(Semaphore::new(
+                    config.max_pipeline_depth.clamp(1, Semaphore::MAX_PERMITS),
+                )),
+                requests_sent: Mutex::new(0),
             }),
             #[cfg(feature = "runtime")]
             socket_config: None,
-            ssl_mode,
+            ssl_mode: config.ssl_mode,
+            ssl_negotiation: config.ssl_negotiation,
             process_id,
             secret_key,
+            connection_info,
         }
     }
 
@@ -236,6 +381,15 @@ impl Client {
         prepare::prepare(&self.inner, query, parameter_types).await
     }
 
+    /// Prepares several statements at once, pipelining them to the server in a single round trip.
+    ///
+    /// This is equivalent to calling `prepare` on each query, but avoids waiting on the server between them. If any
+    /// query fails to prepare, none of the statements are returned and the index of the failing query is available via
+    /// [`Error::statement_index`].
+    pub async fn prepare_batch(&self, queries: &[&str]) -> Result<Vec<Statement>, Error> {
+        prepare::prepare_batch(&self.inner, queries).await
+    }
+
     /// Executes a statement, returning a vector of the resulting rows.
     ///
     /// A statement may contain parameters, specified by `$n`, where `n` is the index of the parameter of the list
@@ -258,6 +412,73 @@ impl Client {
             .await
     }
 
+    /// Like `query`, but gives up on the query if it has not completed by `deadline`.
+    ///
+    /// The deadline covers preparing the statement, if a query string is passed, as well as executing it. If it passes,
+    /// a cancellation request is sent to the server using the provided TLS connector so that the backend stops working
+    /// on the request, and its remaining responses are drained so that the client can continue to be used. A timeout
+    /// error is returned in that case, unless the query completes before the cancellation takes effect. A statement
+    /// which is only prepared after the deadline is never executed.
+    ///
+    /// Sending the cancellation and waiting for it to take effect is given at most 5 more seconds, after which the
+    /// timeout error is returned regardless.
+    ///
+    /// A cancellation request applies to whatever the backend is running when it arrives, not to a particular query.
+    /// If other requests are pipelined on the same client, the query may finish just before the request arrives and
+    /// the next one in the pipeline be cancelled instead, failing with `QUERY_CANCELED`.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub async fn query_deadline<T, U>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+        deadline: Instant,
+        tls: U,
+    ) -> Result<Vec<Row>, Error>
+    where
+        T: ?Sized + ToStatement,
+        U: MakeTlsConnect<Socket>,
+    {
+        let prepare = statement.__convert().into_statement(self);
+        pin_mut!(prepare);
+        let statement = match time::timeout_at(deadline.into(), &mut prepare).await {
+            Ok(statement) => statement?,
+            Err(_) => {
+                // preparing can wait on locks, so it's cancelled too, but the statement isn't executed regardless
+                self.cancel_after_deadline(prepare, tls).await?;
+                return Err(Error::__private_api_timeout());
+            }
+        };
+
+        let query = self.query(&statement, params);
+        pin_mut!(query);
+        match time::timeout_at(deadline.into(), &mut query).await {
+            Ok(result) => result,
+            Err(_) => self.cancel_after_deadline(query, tls).await,
+        }
+    }
+
+    /// Cancels the request the future is waiting on, giving it a grace period to finish.
+    #[cfg(feature = "runtime")]
+    async fn cancel_after_deadline<F, R, U>(&self, future: F, tls: U) -> Result<R, Error>
+    where
+        F: Future<Output = Result<R, Error>>,
+        U: MakeTlsConnect<Socket>,
+    {
+        let cancelled = async {
+            self.cancel_token().cancel_query(tls).await?;
+            future.await
+        };
+        match time::timeout(CANCEL_GRACE_PERIOD, cancelled).await {
+            Ok(Err(e)) if e.code() == Some(&SqlState::QUERY_CANCELED) => {
+                Err(Error::__private_api_timeout())
+            }
+            Ok(result) => result,
+            Err(_) => Err(Error::__private_api_timeout()),
+        }
+    }
+
     /// Executes a statement which returns a single row, returning it.
     ///
     /// Returns an error if the query does not return exactly one row.
@@ -363,7 +584,32 @@ impl Client {
         I::IntoIter: ExactSizeIterator,
     {
         let statement = statement.__convert().into_statement(self).await?;
-        query::query(&self.inner, statement, params).await
+        query::query(&self.inner, statement, params, None).await
+    }
+
+    /// Like [`query`], but also returns the notices the server sent while executing the statement.
+    ///
+    /// Notices raised by the statement, for example with `RAISE NOTICE` in a function or trigger it invokes, are
+    /// returned in the order they were received. They are still delivered through the `Connection` as well, so notice
+    /// handlers and logging continue to see them. If the statement fails, the notices collected so far are lost.
+    ///
+    /// [`query`]: #method.query
+    pub async fn query_with_notices<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Row>, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        let notices = NoticeSink::default();
+        let statement = statement.__convert().into_statement(self).await?;
+        let rows = query::query(&self.inner, statement, slice_iter(params), Some(&notices))
+            .await?
+            .try_collect()
+            .await?;
+        let notices = mem::take(&mut *notices.lock());
+        Ok((rows, notices))
     }
 
     /// Executes a statement, returning the number of rows modified.
@@ -405,7 +651,53 @@ impl Client {
         I::IntoIter: ExactSizeIterator,
     {
         let statement = statement.__convert().into_statement(self).await?;
-        query::execute(self.inner(), statement, params).await
+        query::execute(self.inner(), statement, params, None).await
+    }
+
+    /// Like [`execute`], but also returns the notices the server sent while executing the statement.
+    ///
+    /// See [`query_with_notices`] for details.
+    ///
+    /// [`execute`]: #method.execute
+    /// [`query_with_notices`]: #method.query_with_notices
+    pub async fn execute_with_notices<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(u64, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        let notices = NoticeSink::default();
+        let statement = statement.__convert().into_statement(self).await?;
+        let rows =
+            query::execute(self.inner(), statement, slice_iter(params), Some(&notices)).await?;
+        let notices = mem::take(&mut *notices.lock());
+        Ok((rows, notices))
+    }
+
+    /// Like [`execute`], but streams the value of the statement's final parameter from a reader.
+    ///
+    /// This avoids holding very large values, such as multi-gigabyte `BYTEA`s, in memory. The `params` bind all but
+    /// the last parameter of the statement. Note that the server limits the total size of a single message to roughly
+    /// 1GB.
+    ///
+    /// If the reader fails or produces fewer bytes than promised, the statement is not executed and a conversion
+    /// error for the streamed parameter is returned.
+    ///
+    /// [`execute`]: #method.execute
+    pub async fn execute_streaming<T, R>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+        value: StreamingParam<R>,
+    ) -> Result<u64, Error>
+    where
+        T: ?Sized + ToStatement,
+        R: AsyncRead + Send + 'static,
+    {
+        let statement = statement.__convert().into_statement(self).await?;
+        streaming_param::execute(self.inner(), statement, params, value).await
     }
 
     /// Executes a `COPY FROM STDIN` statement, returning a sink used to write the copy data.
@@ -445,6 +737,20 @@ impl Client {
         copy_out::copy_out_simple(self.inner(), query).await
     }
 
+    /// Returns a builder for a `COPY ... FROM STDIN` query into the specified table.
+    ///
+    /// The builder generates the query from a column list and format options, quoting all names and values.
+    pub fn build_copy_in(&self, table: &str) -> CopyInBuilder<'_> {
+        CopyInBuilder::new(self, table)
+    }
+
+    /// Returns a builder for a `COPY ... TO STDOUT` query from the specified table.
+    ///
+    /// The builder generates the query from a column list and format options, quoting all names and values.
+    pub fn build_copy_out(&self, table: &str) -> CopyOutBuilder<'_> {
+        CopyOutBuilder::new(self, table)
+    }
+
     /// Executes a CopyBoth query, returning a combined Stream+Sink type to read and write copy
     /// data.
     pub async fn copy_both_simple<T>(&self, query: &str) -> Result<CopyBothDuplex<T>, Error>
@@ -475,6 +781,25 @@ impl Client {
         simple_query::simple_query(self.inner(), query).await
     }
 
+    /// Sends raw frontend protocol messages to the server, returning a stream of the raw backend messages sent in
+    /// response.
+    ///
+    /// This is a low-level escape hatch for protocol features this crate does not model. `messages` must contain
+    /// complete, encoded messages, and the last must be the only one which completes a request: a `Sync`, `Query` or
+    /// `FunctionCall`. Terminate and `COPY` messages may not be sent. Messages which don't meet these requirements are
+    /// rejected without being sent. The responses are returned as-is, including any `ErrorResponse` and the final
+    /// `ReadyForQuery`.
+    ///
+    /// # Warning
+    ///
+    /// The server's state is trusted to the caller. Messages which leave the session in a state the client doesn't
+    /// expect - such as leaving an unterminated transaction - will cause later requests to fail or hang. Starting a
+    /// `COPY`, for example with a `COPY ... FROM STDIN` query, can't be completed with raw messages, so it closes the
+    /// connection after the `CopyInResponse`, `CopyOutResponse` or `CopyBothResponse` has been returned.
+    pub async fn send_raw(&self, messages: Bytes) -> Result<RawMessageStream, Error> {
+        raw_message::send_raw(self.inner(), messages).await
+    }
+
     /// Executes a sequence of SQL statements using the simple query protocol.
     ///
     /// Statements should be separated by semicolons. If an error occurs, execution of the sequence will stop at that
@@ -511,7 +836,7 @@ impl Client {
                 let _ = self
                     .client
                     .inner()
-                    .send(RequestMessages::Single(FrontendMessage::Raw(buf)));
+                    .send_nowait(RequestMessages::Single(FrontendMessage::Raw(buf)));
             }
         }
 
@@ -540,6 +865,20 @@ impl Client {
         TransactionBuilder::new(self)
     }
 
+    /// Returns information about how the connection was established, such as the authentication method and whether
+    /// TLS is in use.
+    pub fn connection_info(&self) -> ConnectionInfo {
+        self.connection_info
+    }
+
+    /// Fetches a snapshot of the session's state, such as the current user and database and whether the server is a
+    /// standby, in a single round trip.
+    ///
+    /// This is useful for diagnostics, for example to confirm which server `target_session_attrs` selected.
+    pub async fn session_info(&self) -> Result<SessionInfo, Error> {
+        session_info::session_info(self).await
+    }
+
     /// Constructs a cancellation token that can later be used to request cancellation of a query running on the
     /// connection associated with this client.
     pub fn cancel_token(&self) -> CancelToken {
@@ -547,6 +886,7 @@ impl Client {
             #[cfg(feature = "runtime")]
             socket_config: self.socket_config.clone(),
             ssl_mode: self.ssl_mode,
+            ssl_negotiation: self.ssl_negotiation,
             process_id: self.process_id,
             secret_key: self.secret_key,
         }
@@ -587,6 +927,17 @@ impl Client {
         self.inner().clear_type_cache();
     }
 
+    /// Adds type definitions to the client's type information cache.
+    ///
+    /// The client will use these definitions for the types' OIDs rather than loading them from the database. This
+    /// avoids the catalog queries entirely, which matters when running behind a transaction-mode connection pooler
+    /// where they may be answered by a different backend. Registered types are forgotten by `clear_type_cache`.
+    pub fn register_types(&self, types: &[Type]) {
+        for type_ in types {
+            self.inner().set_type(type_.oid(), type_);
+        }
+    }
+
     /// Determines if the connection to the server has already closed.
     ///
     /// In that case, all future queries will fail.
@@ -594,6 +945,16 @@ impl Client {
         self.inner.sender.is_closed()
     }
 
+    /// Returns the error the server reported before terminating the connection, if any.
+    ///
+    /// This is set when the server sends a `FATAL` or `PANIC` error outside of any query, for example when the
+    /// connection is terminated by an administrator or the server is shutting down. The same error is also available via
+    /// `Error::as_db_error` on errors returned by requests made after the connection closed. It can be used to
+    /// distinguish a server-initiated shutdown from a network failure.
+    pub fn close_reason(&self) -> Option<DbError> {
+        self.inner.close_reason.lock().clone()
+    }
+
     #[doc(hidden)]
     pub fn __private_api_close(&mut self) {
         self.inner.sender.close_channel()
@@ -26,6 +26,29 @@ impl BackendMessages {
     }
 }
 
+impl BackendMessages {
+    pub fn next_raw(&mut self) -> io::Result<Option<Bytes>> {
+        match backend::Header::parse(&self.0)? {
+            Some(header) => Ok(Some(self.0.split_to(header.len() as usize + 1).freeze())),
+            None => Ok(None),
+        }
+    }
+
+    /// Determines if any of the messages starts a `COPY`.
+    pub fn starts_copy(&self) -> bool {
+        let mut buf = &self.0[..];
+        while let Ok(Some(header)) = backend::Header::parse(buf) {
+            match header.tag() {
+                backend::COPY_IN_RESPONSE_TAG
+                | backend::COPY_OUT_RESPONSE_TAG
+                | backend::COPY_BOTH_RESPONSE_TAG => return true,
+                _ => buf = &buf[header.len() as usize + 1..],
+            }
+        }
+        false
+    }
+}
+
 impl FallibleIterator for BackendMessages {
     type Item = backend::Message;
     type Error = io::Error;
@@ -35,7 +58,10 @@ impl FallibleIterator for BackendMessages {
     }
 }
 
-pub struct PostgresCodec;
+pub struct PostgresCodec {
+    pub max_message_size: Option<usize>,
+    pub max_rows: Option<usize>,
+}
 
 impl Encoder<FrontendMessage> for PostgresCodec {
     type Error = io::Error;
@@ -56,10 +82,22 @@ impl Decoder for PostgresCodec {
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<BackendMessage>, io::Error> {
         let mut idx = 0;
+        let mut rows = 0;
         let mut request_complete = false;
 
         while let Some(header) = backend::Header::parse(&src[idx..])? {
             let len = header.len() as usize + 1;
+            if let Some(max) = self.max_message_size {
+                if len > max {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "message of {} bytes exceeds the maximum size of {}",
+                            len, max
+                        ),
+                    ));
+                }
+            }
             if src[idx..].len() < len {
                 break;
             }
@@ -84,6 +122,14 @@ impl Decoder for PostgresCodec {
                 request_complete = true;
                 break;
             }
+
+            // the rest stay in the read buffer, which isn't refilled until they've been passed on
+            if header.tag() == backend::DATA_ROW_TAG {
+                rows += 1;
+                if self.max_rows == Some(rows) {
+                    break;
+                }
+            }
         }
 
         if idx == 0 {
@@ -96,3 +142,54 @@ impl Decoder for PostgresCodec {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bytes::BufMut;
+
+    fn message(tag: u8, body: &[u8], buf: &mut BytesMut) {
+        buf.put_u8(tag);
+        buf.put_i32(body.len() as i32 + 4);
+        buf.put_slice(body);
+    }
+
+    fn rows_and_completion(messages: BackendMessage) -> (usize, bool) {
+        match messages {
+            BackendMessage::Normal {
+                mut messages,
+                request_complete,
+            } => {
+                let mut rows = 0;
+                while let Some(message) = messages.next().unwrap() {
+                    if let backend::Message::DataRow(_) = message {
+                        rows += 1;
+                    }
+                }
+                (rows, request_complete)
+            }
+            BackendMessage::Async(_) => panic!("unexpected async message"),
+        }
+    }
+
+    #[test]
+    fn max_rows() {
+        let mut codec = PostgresCodec {
+            max_message_size: None,
+            max_rows: Some(2),
+        };
+        let mut buf = BytesMut::new();
+        for _ in 0..5 {
+            message(backend::DATA_ROW_TAG, &0i16.to_be_bytes(), &mut buf);
+        }
+        message(backend::COMMAND_COMPLETE_TAG, b"SELECT 5\0", &mut buf);
+        message(backend::READY_FOR_QUERY_TAG, b"I", &mut buf);
+
+        let mut batches = vec![];
+        while let Some(messages) = codec.decode(&mut buf).unwrap() {
+            batches.push(rows_and_completion(messages));
+        }
+        assert_eq!(batches, [(2, false), (2, false), (1, true)]);
+        assert!(buf.is_empty());
+    }
+}
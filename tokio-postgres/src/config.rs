@@ -1,8 +1,10 @@
 //! Connection configuration.
 
 #[cfg(feature = "runtime")]
-use crate::connect::connect;
+use crate::connect::{connect, connect_with_retry};
 use crate::connect_raw::connect_raw;
+#[cfg(feature = "runtime")]
+use crate::dns_cache::DnsCache;
 #[cfg(not(target_arch = "wasm32"))]
 use crate::keepalive::KeepaliveConfig;
 #[cfg(feature = "runtime")]
@@ -11,10 +13,20 @@ use crate::tls::TlsConnect;
 #[cfg(feature = "runtime")]
 use crate::Socket;
 use crate::{Client, Connection, Error};
+/// The socket passed to `Config::configure_socket`.
+///
+/// This is re-exported from `socket2`, which makes it a public dependency: upgrading `socket2` to an incompatible
+/// version is a breaking change of this crate.
+#[cfg(feature = "runtime")]
+pub use socket2::SockRef;
 use std::borrow::Cow;
 #[cfg(unix)]
 use std::ffi::OsStr;
+#[cfg(feature = "runtime")]
+use std::io;
 use std::net::IpAddr;
+#[cfg(feature = "runtime")]
+use std::net::SocketAddr;
 use std::ops::Deref;
 #[cfg(unix)]
 use std::os::unix::ffi::OsStrExt;
@@ -22,6 +34,8 @@ use std::os::unix::ffi::OsStrExt;
 use std::path::{Path, PathBuf};
 use std::str;
 use std::str::FromStr;
+#[cfg(feature = "runtime")]
+use std::sync::Arc;
 use std::time::Duration;
 use std::{error, fmt, iter, mem};
 use tokio::io::{AsyncRead, AsyncWrite};
@@ -44,10 +58,44 @@ pub enum TargetSessionAttrs {
 pub enum SslMode {
     /// Do not use TLS.
     Disable,
+    /// Attempt to connect without TLS, but retry with TLS if the server's `pg_hba.conf` rejects the connection.
+    Allow,
     /// Attempt to connect with TLS but allow sessions without.
     Prefer,
     /// Require the use of TLS.
     Require,
+    /// Require the use of TLS, and a server certificate signed by a trusted certificate authority.
+    ///
+    /// Certificates are checked by the TLS connector, so it must be configured to verify the certificate chain.
+    VerifyCa,
+    /// Require the use of TLS, and a server certificate signed by a trusted certificate authority which matches the
+    /// host name.
+    ///
+    /// Certificates are checked by the TLS connector, so it must be configured to verify the certificate chain and
+    /// host name.
+    VerifyFull,
+}
+
+impl SslMode {
+    pub(crate) fn requires_tls(self) -> bool {
+        matches!(
+            self,
+            SslMode::Require | SslMode::VerifyCa | SslMode::VerifyFull
+        )
+    }
+}
+
+/// TLS negotiation configuration.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SslNegotiation {
+    /// Ask the server whether it supports TLS with a plaintext `SSLRequest` before starting the TLS handshake.
+    Postgres,
+    /// Start the TLS handshake immediately, using the `postgresql` ALPN protocol.
+    ///
+    /// This saves a round trip, but requires Postgres 17 or newer and an `SslMode` of `Require`, `VerifyCa` or
+    /// `VerifyFull`.
+    Direct,
 }
 
 /// Channel binding configuration.
@@ -94,6 +142,58 @@ pub enum Host {
     Unix(PathBuf),
 }
 
+/// A policy controlling how [`Config::connect_with_retry`] retries failed connection attempts.
+///
+/// Only failures for which [`Error::is_retryable`] returns `true` are retried. The delay between attempts starts at
+/// the initial backoff and doubles after each failed attempt, up to the maximum backoff.
+#[cfg(feature = "runtime")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub(crate) max_attempts: u32,
+    pub(crate) initial_backoff: Duration,
+    pub(crate) max_backoff: Duration,
+}
+
+#[cfg(feature = "runtime")]
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy::new()
+    }
+}
+
+#[cfg(feature = "runtime")]
+impl RetryPolicy {
+    /// Creates a new policy making at most 5 attempts, with a backoff starting at 100 milliseconds and capped at 10
+    /// seconds.
+    pub fn new() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+
+    /// Sets the maximum number of connection attempts, including the first one.
+    ///
+    /// A value of 0 is treated as 1.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Sets the delay before the first retry.
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Sets the upper bound on the delay between attempts.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+}
+
 /// Connection configuration.
 ///
 /// Configuration can be parsed from libpq-style connection strings. These strings come in two formats:
@@ -110,8 +210,11 @@ pub enum Host {
 /// * `dbname` - The name of the database to connect to. Defaults to the username.
 /// * `options` - Command line options used to configure the server.
 /// * `application_name` - Sets the `application_name` parameter on the server.
-/// * `sslmode` - Controls usage of TLS. If set to `disable`, TLS will not be used. If set to `prefer`, TLS will be used
-///     if available, but not used otherwise. If set to `require`, TLS will be forced to be used. Defaults to `prefer`.
+/// * `fallback_application_name` - Sets the `application_name` parameter if `application_name` is not set.
+/// * `default_transaction_read_only` - If set to `on`, `true`, `yes` or `1`, transactions are read-only by default.
+/// * `sslmode` - Controls usage of TLS, as one of `disable`, `allow`, `prefer`, `require`, `verify-ca`, or `verify-full`.
+///     See `SslMode` for their meanings. Defaults to `prefer`.
+/// * `sslnegotiation` - If set to `direct`, the TLS handshake starts immediately rather than after an `SSLRequest`.
 /// * `host` - The host to connect to. On Unix platforms, if the host starts with a `/` character it is treated as the
 ///     path to the directory containing Unix domain sockets. Otherwise, it is treated as a hostname. Multiple hosts
 ///     can be specified, separated by commas. Each host will be tried in turn when connecting. Required if connecting
@@ -137,6 +240,7 @@ pub enum Host {
 /// * `tcp_user_timeout` - The time limit that transmitted data may remain unacknowledged before a connection is forcibly closed.
 ///     This is ignored for Unix domain socket connections. It is only supported on systems where TCP_USER_TIMEOUT is available
 ///     and will default to the system default if omitted or set to 0; on other systems, it has no effect.
+/// * `requirepeer` - The operating system user the server must be running as, checked for Unix socket connections.
 /// * `keepalives` - Controls the use of TCP keepalive. A value of 0 disables keepalive and nonzero integers enable it.
 ///     This option is ignored when connecting with Unix sockets. Defaults to on.
 /// * `keepalives_idle` - The number of seconds of inactivity after which a keepalive message is sent to the server.
@@ -144,7 +248,7 @@ pub enum Host {
 /// * `keepalives_interval` - The time interval between TCP keepalive probes.
 ///     This option is ignored when connecting with Unix sockets.
 /// * `keepalives_retries` - The maximum number of TCP keepalive probes that will be sent before dropping a connection.
-///     This option is ignored when connecting with Unix sockets.
+///     This option is ignored when connecting with Unix sockets. Also accepted as `keepalives_count`, as in libpq.
 /// * `target_session_attrs` - Specifies requirements of the session. If set to `read-write`, the client will check that
 ///     the `transaction_read_write` session parameter is set to `on`. This can be used to connect to the primary server
 ///     in a database cluster as opposed to the secondary read-only mirrors. Defaults to `all`.
@@ -157,6 +261,7 @@ pub enum Host {
 ///     `disable`, hosts and addresses will be tried in the order provided. If set to `random`, hosts will be tried
 ///     in a random order, and the IP addresses resolved from a hostname will also be tried in a random order. Defaults
 ///     to `disable`.
+/// * `srv_lookup` - If set to `on`, host names are first looked up as DNS SRV records. Requires the `srv` feature.
 ///
 /// ## Examples
 ///
@@ -207,21 +312,73 @@ pub struct Config {
     pub(crate) dbname: Option<String>,
     pub(crate) options: Option<String>,
     pub(crate) application_name: Option<String>,
+    pub(crate) fallback_application_name: Option<String>,
+    pub(crate) default_transaction_read_only: bool,
     pub(crate) ssl_mode: SslMode,
+    pub(crate) ssl_negotiation: SslNegotiation,
     pub(crate) host: Vec<Host>,
     pub(crate) hostaddr: Vec<IpAddr>,
     pub(crate) port: Vec<u16>,
     pub(crate) connect_timeout: Option<Duration>,
     pub(crate) tcp_user_timeout: Option<Duration>,
+    pub(crate) tcp_nodelay: bool,
+    pub(crate) require_peer: Option<String>,
     pub(crate) keepalives: bool,
     #[cfg(not(target_arch = "wasm32"))]
     pub(crate) keepalive_config: KeepaliveConfig,
     pub(crate) target_session_attrs: TargetSessionAttrs,
     pub(crate) channel_binding: ChannelBinding,
+    pub(crate) scram_min_iterations: u32,
     pub(crate) load_balance_hosts: LoadBalanceHosts,
     pub(crate) replication_mode: Option<ReplicationMode>,
+    pub(crate) max_backend_message_size: Option<usize>,
+    pub(crate) read_buffer_size: Option<usize>,
+    pub(crate) write_buffer_size: Option<usize>,
+    pub(crate) row_buffer_limit: Option<usize>,
+    pub(crate) statement_name_prefix: Option<String>,
+    pub(crate) pgbouncer_mode: bool,
+    pub(crate) auto_reprepare: bool,
+    pub(crate) max_pipeline_depth: usize,
+    #[cfg(feature = "runtime")]
+    pub(crate) idle_ping_interval: Option<Duration>,
+    #[cfg(feature = "runtime")]
+    pub(crate) socket_read_timeout: Option<Duration>,
+    #[cfg(feature = "runtime")]
+    pub(crate) dns_cache_ttl: Option<Duration>,
+    #[cfg(feature = "runtime")]
+    pub(crate) dns_cache: DnsCache,
+    #[cfg(feature = "runtime")]
+    pub(crate) socket_configurer: Option<SocketConfigurer>,
+    #[cfg(feature = "srv")]
+    pub(crate) srv_lookup: bool,
 }
 
+/// The function set with `Config::configure_socket`.
+#[cfg(feature = "runtime")]
+#[derive(Clone)]
+pub(crate) struct SocketConfigurer(pub Arc<dyn Fn(SockRef<'_>) -> io::Result<()> + Send + Sync>);
+
+#[cfg(feature = "runtime")]
+impl SocketConfigurer {
+    /// Calls the function with `socket`, unless it is a Unix socket.
+    pub(crate) fn configure(&self, socket: &Socket) -> Result<(), Error> {
+        match socket.tcp_sock_ref() {
+            Some(sock_ref) => (self.0)(sock_ref).map_err(Error::connect),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "runtime")]
+impl PartialEq for SocketConfigurer {
+    fn eq(&self, other: &SocketConfigurer) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(feature = "runtime")]
+impl Eq for SocketConfigurer {}
+
 impl Default for Config {
     fn default() -> Config {
         Config::new()
@@ -237,12 +394,17 @@ impl Config {
             dbname: None,
             options: None,
             application_name: None,
+            fallback_application_name: None,
+            default_transaction_read_only: false,
             ssl_mode: SslMode::Prefer,
+            ssl_negotiation: SslNegotiation::Postgres,
             host: vec![],
             hostaddr: vec![],
             port: vec![],
             connect_timeout: None,
             tcp_user_timeout: None,
+            tcp_nodelay: true,
+            require_peer: None,
             keepalives: true,
             #[cfg(not(target_arch = "wasm32"))]
             keepalive_config: KeepaliveConfig {
@@ -252,8 +414,29 @@ impl Config {
             },
             target_session_attrs: TargetSessionAttrs::Any,
             channel_binding: ChannelBinding::Prefer,
+            scram_min_iterations: 4096,
             load_balance_hosts: LoadBalanceHosts::Disable,
             replication_mode: None,
+            max_backend_message_size: None,
+            read_buffer_size: None,
+            write_buffer_size: None,
+            row_buffer_limit: None,
+            statement_name_prefix: None,
+            pgbouncer_mode: false,
+            auto_reprepare: false,
+            max_pipeline_depth: 1024,
+            #[cfg(feature = "runtime")]
+            idle_ping_interval: None,
+            #[cfg(feature = "runtime")]
+            socket_read_timeout: None,
+            #[cfg(feature = "runtime")]
+            dns_cache_ttl: None,
+            #[cfg(feature = "runtime")]
+            dns_cache: DnsCache::default(),
+            #[cfg(feature = "runtime")]
+            socket_configurer: None,
+            #[cfg(feature = "srv")]
+            srv_lookup: false,
         }
     }
 
@@ -301,11 +484,35 @@ impl Config {
     }
 
     /// Sets command line options used to configure the server.
+    ///
+    /// The server splits the options into arguments at whitespace, so spaces and backslashes within an argument must be
+    /// escaped with a backslash, as in `-c application_name=my\ app`. Quotes have no special meaning. The `option`
+    /// method adds a runtime parameter with this escaping applied.
     pub fn options(&mut self, options: &str) -> &mut Config {
         self.options = Some(options.to_string());
         self
     }
 
+    /// Adds a runtime parameter to be set on the server, such as `search_path`, to the command line options.
+    ///
+    /// This appends `-c name=value` to the options, escaping any whitespace or backslashes so that the server sees
+    /// the value exactly as given.
+    pub fn option(&mut self, name: &str, value: &str) -> &mut Config {
+        let mut options = self.options.take().unwrap_or_default();
+        if !options.is_empty() {
+            options.push(' ');
+        }
+        options.push_str("-c ");
+        for c in name.chars().chain(Some('=')).chain(value.chars()) {
+            if c.is_ascii_whitespace() || c == '\\' {
+                options.push('\\');
+            }
+            options.push(c);
+        }
+        self.options = Some(options);
+        self
+    }
+
     /// Gets the command line options used to configure the server, if the
     /// options have been set with the `options` method.
     pub fn get_options(&self) -> Option<&str> {
@@ -324,8 +531,46 @@ impl Config {
         self.application_name.as_deref()
     }
 
+    /// Sets a default value for the `application_name` runtime parameter.
+    ///
+    /// It is only sent if `application_name` has not been set, which allows libraries to name their connections
+    /// without overriding a name configured by the user.
+    pub fn fallback_application_name(&mut self, fallback_application_name: &str) -> &mut Config {
+        self.fallback_application_name = Some(fallback_application_name.to_string());
+        self
+    }
+
+    /// Gets the value of the `fallback_application_name` runtime parameter, if it has
+    /// been set with the `fallback_application_name` method.
+    pub fn get_fallback_application_name(&self) -> Option<&str> {
+        self.fallback_application_name.as_deref()
+    }
+
+    /// Sets the `default_transaction_read_only` runtime parameter when connecting.
+    ///
+    /// When enabled, statements which write to the database fail at the server unless the transaction is explicitly
+    /// made read-write, guarding against accidental writes through connections meant for reads. Defaults to `false`.
+    pub fn default_transaction_read_only(
+        &mut self,
+        default_transaction_read_only: bool,
+    ) -> &mut Config {
+        self.default_transaction_read_only = default_transaction_read_only;
+        self
+    }
+
+    /// Reports whether transactions will be read-only by default.
+    pub fn get_default_transaction_read_only(&self) -> bool {
+        self.default_transaction_read_only
+    }
+
     /// Sets the SSL configuration.
     ///
+    /// The `verify-ca` and `verify-full` modes require TLS in the same way as `require`, and the certificate checks
+    /// they call for are left to the TLS connector, which is passed the mode by
+    /// `MakeTlsConnect::make_tls_connect_with_negotiation`. The `allow` mode only falls back to TLS when the `Config` opens the
+    /// connection itself, not with `connect_raw`, and only if the server rejects the connection with
+    /// `INVALID_AUTHORIZATION_SPECIFICATION`, as `pg_hba.conf` does when it requires TLS.
+    ///
     /// Defaults to `prefer`.
     pub fn ssl_mode(&mut self, ssl_mode: SslMode) -> &mut Config {
         self.ssl_mode = ssl_mode;
@@ -337,6 +582,22 @@ impl Config {
         self.ssl_mode
     }
 
+    /// Sets how TLS is negotiated with the server.
+    ///
+    /// With `SslNegotiation::Direct`, the TLS connector must offer the `postgresql` ALPN protocol, which the server
+    /// requires for direct connections. The connector is passed the negotiation by
+    /// `MakeTlsConnect::make_tls_connect_with_negotiation`, so it can offer the protocol only then. Defaults to
+    /// `postgres`.
+    pub fn ssl_negotiation(&mut self, ssl_negotiation: SslNegotiation) -> &mut Config {
+        self.ssl_negotiation = ssl_negotiation;
+        self
+    }
+
+    /// Gets how TLS is negotiated with the server.
+    pub fn get_ssl_negotiation(&self) -> SslNegotiation {
+        self.ssl_negotiation
+    }
+
     /// Adds a host to the configuration.
     ///
     /// Multiple hosts can be specified by calling this method multiple times, and each will be tried in order. On Unix
@@ -431,6 +692,35 @@ impl Config {
         self.tcp_user_timeout.as_ref()
     }
 
+    /// Controls the use of `TCP_NODELAY`, which disables Nagle's algorithm.
+    ///
+    /// Disabling this can reduce the number of packets sent for bulk transfers at the cost of latency. This is ignored
+    /// for Unix domain socket connections. Defaults to `true`.
+    pub fn tcp_nodelay(&mut self, tcp_nodelay: bool) -> &mut Config {
+        self.tcp_nodelay = tcp_nodelay;
+        self
+    }
+
+    /// Reports whether `TCP_NODELAY` will be set on the connection.
+    pub fn get_tcp_nodelay(&self) -> bool {
+        self.tcp_nodelay
+    }
+
+    /// Requires the server process to be running as the specified operating system user.
+    ///
+    /// After connecting to a Unix domain socket, the credentials of the process on the other end are checked, and the
+    /// connection fails if its user name does not match. This guards against another local process impersonating the
+    /// server. This is ignored for TCP connections.
+    pub fn require_peer(&mut self, require_peer: &str) -> &mut Config {
+        self.require_peer = Some(require_peer.to_string());
+        self
+    }
+
+    /// Gets the required server user name, if one has been set with the `require_peer` method.
+    pub fn get_require_peer(&self) -> Option<&str> {
+        self.require_peer.as_deref()
+    }
+
     /// Controls the use of TCP keepalive.
     ///
     /// This is ignored for Unix domain socket connections. Defaults to `true`.
@@ -521,6 +811,21 @@ impl Config {
         self.channel_binding
     }
 
+    /// Sets the minimum SCRAM iteration count the server may request during authentication.
+    ///
+    /// A server, or an intermediary impersonating one, could otherwise weaken the password hash by requesting very few
+    /// iterations. Authentication fails if the server asks for fewer than this. Defaults to 4096, the server's
+    /// default.
+    pub fn scram_min_iterations(&mut self, scram_min_iterations: u32) -> &mut Config {
+        self.scram_min_iterations = scram_min_iterations;
+        self
+    }
+
+    /// Gets the minimum SCRAM iteration count.
+    pub fn get_scram_min_iterations(&self) -> u32 {
+        self.scram_min_iterations
+    }
+
     /// Sets the host load balancing behavior.
     ///
     /// Defaults to `disable`.
@@ -545,6 +850,261 @@ impl Config {
         self.replication_mode
     }
 
+    /// Sets the maximum size in bytes of a single message accepted from the server.
+    ///
+    /// A message whose length prefix exceeds this limit is treated as a protocol error and closes the connection,
+    /// rather than buffering it. This guards against buggy or malicious servers. Defaults to no limit.
+    pub fn max_backend_message_size(&mut self, max_backend_message_size: usize) -> &mut Config {
+        self.max_backend_message_size = Some(max_backend_message_size);
+        self
+    }
+
+    /// Gets the maximum backend message size, if one has been set with the `max_backend_message_size` method.
+    pub fn get_max_backend_message_size(&self) -> Option<usize> {
+        self.max_backend_message_size
+    }
+
+    /// Sets the initial capacity of the buffer which data read from the server is stored in.
+    ///
+    /// Larger buffers reduce the number of reads needed to stream large results or `COPY` data, while smaller buffers
+    /// save memory for many mostly idle connections. The buffer still grows to fit larger messages. Defaults to 8 KiB.
+    pub fn read_buffer_size(&mut self, read_buffer_size: usize) -> &mut Config {
+        self.read_buffer_size = Some(read_buffer_size);
+        self
+    }
+
+    /// Gets the read buffer size, if one has been set with the `read_buffer_size` method.
+    pub fn get_read_buffer_size(&self) -> Option<usize> {
+        self.read_buffer_size
+    }
+
+    /// Sets the number of bytes of outgoing requests which are buffered before they are written to the server.
+    ///
+    /// Larger buffers combine writes of many small messages, such as the rows of a `COPY`, into fewer system calls.
+    /// Requests are always written once there is nothing left to buffer, regardless of this size. Defaults to 8 KiB.
+    pub fn write_buffer_size(&mut self, write_buffer_size: usize) -> &mut Config {
+        self.write_buffer_size = Some(write_buffer_size);
+        self
+    }
+
+    /// Gets the write buffer size, if one has been set with the `write_buffer_size` method.
+    pub fn get_write_buffer_size(&self) -> Option<usize> {
+        self.write_buffer_size
+    }
+
+    /// Sets the maximum number of rows which are passed from the connection to a query's row stream at once.
+    ///
+    /// The connection stops reading from the server while a query has unconsumed rows waiting, so this bounds the
+    /// memory used by a large result with a slow consumer to a small multiple of this many rows. Lower limits use less
+    /// memory at the cost of more wakeups. A limit of 0 is treated as 1. Defaults to no limit, in which case rows are
+    /// passed on in batches of however many were read at once.
+    pub fn row_buffer_limit(&mut self, row_buffer_limit: usize) -> &mut Config {
+        self.row_buffer_limit = Some(row_buffer_limit.max(1));
+        self
+    }
+
+    /// Gets the row buffer limit, if one has been set with the `row_buffer_limit` method.
+    pub fn get_row_buffer_limit(&self) -> Option<usize> {
+        self.row_buffer_limit
+    }
+
+    /// Sets a prefix for the names of prepared statements.
+    ///
+    /// Statements are named like `s0`, `s1`, and so on by default. With a prefix of `myapp_` they are instead named
+    /// `myapp_s0`, `myapp_s1`, which makes them easier to identify in `pg_prepared_statements` and the server logs.
+    pub fn statement_name_prefix(&mut self, statement_name_prefix: &str) -> &mut Config {
+        self.statement_name_prefix = Some(statement_name_prefix.to_string());
+        self
+    }
+
+    /// Gets the prepared statement name prefix, if one has been set with the `statement_name_prefix` method.
+    pub fn get_statement_name_prefix(&self) -> Option<&str> {
+        self.statement_name_prefix.as_deref()
+    }
+
+    /// Enables compatibility with connection poolers such as PgBouncer in transaction pooling mode.
+    ///
+    /// Under transaction pooling, successive requests outside of a transaction may be sent to different server
+    /// backends, so state left on a backend by an earlier request can't be relied upon. In this mode:
+    ///
+    /// * Statements are not prepared on the server. Preparing a statement only describes it, and its query is parsed
+    ///   again in the same round trip as each execution. This costs an extra parse per execution, and the server
+    ///   can't reuse query plans.
+    /// * The types of parameters and columns are never looked up in the database catalog. Built-in types work as
+    ///   usual, but custom types such as enums and composites must be registered ahead of time with
+    ///   `Client::register_types`, or preparing a statement which uses them will fail.
+    ///
+    /// Defaults to `false`.
+    pub fn pgbouncer_mode(&mut self, pgbouncer_mode: bool) -> &mut Config {
+        self.pgbouncer_mode = pgbouncer_mode;
+        self
+    }
+
+    /// Reports whether pgbouncer compatibility mode is enabled.
+    pub fn get_pgbouncer_mode(&self) -> bool {
+        self.pgbouncer_mode
+    }
+
+    /// Controls whether statements are prepared again when their result types change.
+    ///
+    /// If a table a prepared statement reads from is altered, for example by a migration adding a column, executing
+    /// the statement fails with a `FEATURE_NOT_SUPPORTED` error saying that the cached plan must not change result
+    /// type. When enabled, the statement is instead prepared again and the query retried once, as happens anyway when
+    /// the server reports the statement doesn't exist. As with that case, this isn't possible while a transaction is
+    /// in progress, since the failure aborts it.
+    ///
+    /// Rows returned after a statement is prepared again are described by its new columns, which may not match
+    /// `Statement::columns`.
+    ///
+    /// Defaults to `false`.
+    pub fn auto_reprepare(&mut self, auto_reprepare: bool) -> &mut Config {
+        self.auto_reprepare = auto_reprepare;
+        self
+    }
+
+    /// Reports whether statements are prepared again when their result types change.
+    pub fn get_auto_reprepare(&self) -> bool {
+        self.auto_reprepare
+    }
+
+    /// Sets the maximum number of requests which may be queued or in flight on the connection at once.
+    ///
+    /// Once this many requests are awaiting their responses, methods making a new request wait for an earlier one to
+    /// complete before sending it, so a producer pipelining many requests can't queue them without limit. The cleanup
+    /// done when statements and portals are dropped doesn't wait. A value of 0 is treated as 1, which disables
+    /// pipelining. Defaults to 1024.
+    pub fn max_pipeline_depth(&mut self, max_pipeline_depth: usize) -> &mut Config {
+        self.max_pipeline_depth = max_pipeline_depth;
+        self
+    }
+
+    /// Gets the maximum number of queued and in-flight requests.
+    pub fn get_max_pipeline_depth(&self) -> usize {
+        self.max_pipeline_depth
+    }
+
+    /// Sets the interval after which an idle connection pings the server.
+    ///
+    /// While no requests are in flight, the `Connection` sends a `Sync` message to the server once per interval. This
+    /// keeps the session alive through middleboxes which drop idle connections, even when TCP keepalives are ignored.
+    /// If the server has not responded to a ping by the time the next one is due, the connection fails with a timeout
+    /// error. Defaults to no pings.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub fn idle_ping_interval(&mut self, idle_ping_interval: Duration) -> &mut Config {
+        self.idle_ping_interval = Some(idle_ping_interval);
+        self
+    }
+
+    /// Gets the idle ping interval, if one has been set with the `idle_ping_interval` method.
+    #[cfg(feature = "runtime")]
+    pub fn get_idle_ping_interval(&self) -> Option<Duration> {
+        self.idle_ping_interval
+    }
+
+    /// Sets how long the connection waits for the server to send anything while a response is outstanding.
+    ///
+    /// If nothing is received from the server within the timeout while a request is waiting on it, the `Connection`
+    /// fails with a timeout error, so that queries on a half-open connection fail rather than hanging forever. The
+    /// timeout restarts whenever a message is sent or received, and time spent waiting on the client, such as for the
+    /// rows of a `COPY ... FROM STDIN` or for a slow consumer of a query's rows, doesn't count towards it. It should be
+    /// longer than the longest running query, since a query doesn't produce any messages until it has results.
+    /// Defaults to no timeout.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub fn socket_read_timeout(&mut self, socket_read_timeout: Duration) -> &mut Config {
+        self.socket_read_timeout = Some(socket_read_timeout);
+        self
+    }
+
+    /// Gets the socket read timeout, if one has been set with the `socket_read_timeout` method.
+    #[cfg(feature = "runtime")]
+    pub fn get_socket_read_timeout(&self) -> Option<Duration> {
+        self.socket_read_timeout
+    }
+
+    /// Sets how long the addresses a host name resolves to are cached for.
+    ///
+    /// When set, connections made within the TTL of a previous resolution of the same host and port reuse its
+    /// addresses rather than resolving the host again. All of the cached addresses are still tried, in the same way
+    /// as freshly resolved ones. The cached addresses are discarded as soon as one of them can't be reached or times
+    /// out, so the next attempt resolves the host again. The cache is shared by clones of this `Config`. Defaults to
+    /// no caching.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub fn dns_cache_ttl(&mut self, dns_cache_ttl: Duration) -> &mut Config {
+        self.dns_cache_ttl = Some(dns_cache_ttl);
+        self
+    }
+
+    /// Gets the DNS cache TTL, if one has been set with the `dns_cache_ttl` method.
+    #[cfg(feature = "runtime")]
+    pub fn get_dns_cache_ttl(&self) -> Option<Duration> {
+        self.dns_cache_ttl
+    }
+
+    #[doc(hidden)]
+    #[cfg(feature = "runtime")]
+    pub fn __private_api_cache_addrs(&self, host: &str, port: u16, addrs: Vec<SocketAddr>) {
+        let ttl = self.dns_cache_ttl.unwrap_or_default();
+        self.dns_cache.insert(host, port, addrs, ttl);
+    }
+
+    #[doc(hidden)]
+    #[cfg(feature = "runtime")]
+    pub fn __private_api_cached_addrs(&self, host: &str, port: u16) -> Option<Vec<SocketAddr>> {
+        self.dns_cache.get(host, port)
+    }
+
+    /// Sets a function which is called to configure each TCP socket once it has connected, before the startup
+    /// handshake.
+    ///
+    /// It is also called for the socket opened to send a cancellation request, such as by
+    /// [`CancelToken::cancel_query`](crate::CancelToken::cancel_query).
+    ///
+    /// This allows setting socket options which have no dedicated configuration method, such as the send and receive
+    /// buffer sizes or the traffic class. It runs after the options this `Config` controls, such as keepalives, have
+    /// been applied, and is not called for Unix sockets. If it returns an error, connecting to that address fails.
+    ///
+    /// The socket is a [`socket2::SockRef`], from version 0.5 of `socket2`.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub fn configure_socket<F>(&mut self, configure_socket: F) -> &mut Config
+    where
+        F: Fn(SockRef<'_>) -> io::Result<()> + Send + Sync + 'static,
+    {
+        self.socket_configurer = Some(SocketConfigurer(Arc::new(configure_socket)));
+        self
+    }
+
+    /// Controls the use of DNS SRV records to discover the hosts to connect to.
+    ///
+    /// If enabled, connecting to a TCP host name without a `hostaddr` first looks up the `_postgresql._tcp` SRV
+    /// records of that name, and tries each target host and port they list in order of priority, picking randomly by
+    /// weight among targets of the same priority as described in RFC 2782. The configured port is ignored for those
+    /// targets, and the target name is used for TLS validation. If the only record has a target of `.`, the service
+    /// is reported as unavailable at that host. If no SRV record exists, or the lookup fails, the host is resolved and
+    /// connected to as usual. Defaults to `false`.
+    ///
+    /// Records are looked up with `hickory-resolver`, using the system's resolver configuration, such as
+    /// `/etc/resolv.conf` on Unix. SRV records are not looked up if it can't be read. IP addresses are never looked
+    /// up. Requires the `srv` Cargo feature.
+    #[cfg(feature = "srv")]
+    pub fn srv_lookup(&mut self, srv_lookup: bool) -> &mut Config {
+        self.srv_lookup = srv_lookup;
+        self
+    }
+
+    /// Reports whether DNS SRV records are used to discover hosts.
+    #[cfg(feature = "srv")]
+    pub fn get_srv_lookup(&self) -> bool {
+        self.srv_lookup
+    }
+
     fn param(&mut self, key: &str, value: &str) -> Result<(), Error> {
         match key {
             "user" => {
@@ -562,15 +1122,46 @@ impl Config {
             "application_name" => {
                 self.application_name(value);
             }
+            "fallback_application_name" => {
+                self.fallback_application_name(value);
+            }
+            "default_transaction_read_only" => {
+                // the spellings of a boolean the server accepts
+                let read_only = match &*value.to_ascii_lowercase() {
+                    "on" | "true" | "yes" | "1" => true,
+                    "off" | "false" | "no" | "0" => false,
+                    _ => {
+                        return Err(Error::config_parse(Box::new(InvalidValue(
+                            "default_transaction_read_only",
+                        ))))
+                    }
+                };
+                self.default_transaction_read_only(read_only);
+            }
             "sslmode" => {
                 let mode = match value {
                     "disable" => SslMode::Disable,
+                    "allow" => SslMode::Allow,
                     "prefer" => SslMode::Prefer,
                     "require" => SslMode::Require,
+                    "verify-ca" => SslMode::VerifyCa,
+                    "verify-full" => SslMode::VerifyFull,
                     _ => return Err(Error::config_parse(Box::new(InvalidValue("sslmode")))),
                 };
                 self.ssl_mode(mode);
             }
+            "sslnegotiation" => {
+                let negotiation = match value {
+                    "postgres" => SslNegotiation::Postgres,
+                    "direct" => SslNegotiation::Direct,
+                    _ => {
+                        return Err(Error::config_parse(Box::new(InvalidValue(
+                            "sslnegotiation",
+                        ))))
+                    }
+                };
+                self.ssl_negotiation(negotiation);
+            }
             "host" => {
                 for host in value.split(',') {
                     self.host(host);
@@ -611,6 +1202,9 @@ impl Config {
                     self.tcp_user_timeout(Duration::from_secs(timeout as u64));
                 }
             }
+            "requirepeer" => {
+                self.require_peer(value);
+            }
             #[cfg(not(target_arch = "wasm32"))]
             "keepalives" => {
                 let keepalives = value
@@ -637,11 +1231,18 @@ impl Config {
                 }
             }
             #[cfg(not(target_arch = "wasm32"))]
-            "keepalives_retries" => {
+            "keepalives_retries" | "keepalives_count" => {
                 let keepalives_retries = value.parse::<u32>().map_err(|_| {
-                    Error::config_parse(Box::new(InvalidValue("keepalives_retries")))
+                    let key = if key == "keepalives_count" {
+                        "keepalives_count"
+                    } else {
+                        "keepalives_retries"
+                    };
+                    Error::config_parse(Box::new(InvalidValue(key)))
                 })?;
-                self.keepalives_retries(keepalives_retries);
+                if keepalives_retries > 0 {
+                    self.keepalives_retries(keepalives_retries);
+                }
             }
             "target_session_attrs" => {
                 let target_session_attrs = match value {
@@ -692,6 +1293,15 @@ impl Config {
                     self.replication_mode(mode);
                 }
             }
+            #[cfg(feature = "srv")]
+            "srv_lookup" => {
+                let srv_lookup = match value {
+                    "on" => true,
+                    "off" => false,
+                    _ => return Err(Error::config_parse(Box::new(InvalidValue("srv_lookup")))),
+                };
+                self.srv_lookup(srv_lookup);
+            }
             key => {
                 return Err(Error::config_parse(Box::new(UnknownOption(
                     key.to_string(),
@@ -713,9 +1323,28 @@ impl Config {
         connect(tls, self).await
     }
 
+    /// Opens a connection to a PostgreSQL database, retrying transient failures according to `policy`.
+    ///
+    /// Failures which are not transient, such as authentication errors or an invalid configuration, are returned
+    /// immediately. Otherwise, the error from the last attempt is returned once the policy is exhausted.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub async fn connect_with_retry<T>(
+        &self,
+        tls: T,
+        policy: RetryPolicy,
+    ) -> Result<(Client, Connection<Socket, T::Stream>), Error>
+    where
+        T: MakeTlsConnect<Socket>,
+    {
+        connect_with_retry(tls, self, policy).await
+    }
+
     /// Connects to a PostgreSQL database over an arbitrary stream.
     ///
-    /// All of the settings other than `user`, `password`, `dbname`, `options`, and `application_name` name are ignored.
+    /// All of the settings other than `user`, `password`, `dbname`, `options`, `application_name`, and
+    /// `fallback_application_name` are ignored.
     pub async fn connect_raw<S, T>(
         &self,
         stream: S,
@@ -757,12 +1386,20 @@ impl fmt::Debug for Config {
             .field("dbname", &self.dbname)
             .field("options", &self.options)
             .field("application_name", &self.application_name)
+            .field("fallback_application_name", &self.fallback_application_name)
+            .field(
+                "default_transaction_read_only",
+                &self.default_transaction_read_only,
+            )
             .field("ssl_mode", &self.ssl_mode)
+            .field("ssl_negotiation", &self.ssl_negotiation)
             .field("host", &self.host)
             .field("hostaddr", &self.hostaddr)
             .field("port", &self.port)
             .field("connect_timeout", &self.connect_timeout)
             .field("tcp_user_timeout", &self.tcp_user_timeout)
+            .field("tcp_nodelay", &self.tcp_nodelay)
+            .field("require_peer", &self.require_peer)
             .field("keepalives", &self.keepalives);
 
         #[cfg(not(target_arch = "wasm32"))]
@@ -773,11 +1410,34 @@ impl fmt::Debug for Config {
                 .field("keepalives_retries", &self.keepalive_config.retries);
         }
 
-        config_dbg
+        config_dbg = config_dbg
             .field("target_session_attrs", &self.target_session_attrs)
             .field("channel_binding", &self.channel_binding)
+            .field("scram_min_iterations", &self.scram_min_iterations)
             .field("replication", &self.replication_mode)
-            .finish()
+            .field("max_backend_message_size", &self.max_backend_message_size)
+            .field("read_buffer_size", &self.read_buffer_size)
+            .field("write_buffer_size", &self.write_buffer_size)
+            .field("row_buffer_limit", &self.row_buffer_limit)
+            .field("statement_name_prefix", &self.statement_name_prefix)
+            .field("pgbouncer_mode", &self.pgbouncer_mode)
+            .field("auto_reprepare", &self.auto_reprepare)
+            .field("max_pipeline_depth", &self.max_pipeline_depth);
+
+        #[cfg(feature = "runtime")]
+        {
+            config_dbg = config_dbg
+                .field("idle_ping_interval", &self.idle_ping_interval)
+                .field("socket_read_timeout", &self.socket_read_timeout)
+                .field("dns_cache_ttl", &self.dns_cache_ttl);
+        }
+
+        #[cfg(feature = "srv")]
+        {
+            config_dbg = config_dbg.field("srv_lookup", &self.srv_lookup);
+        }
+
+        config_dbg.finish()
     }
 }
 
@@ -1158,6 +1818,23 @@ mod tests {
 
     use crate::{config::Host, Config};
 
+    #[test]
+    fn option_escaping() {
+        let mut config = Config::new();
+        config
+            .option("search_path", "my schema")
+            .option("application_name", "a\\b");
+        assert_eq!(
+            config.get_options(),
+            Some("-c search_path=my\\ schema -c application_name=a\\\\b")
+        );
+
+        let config = "options='-c search_path=my\\\\ schema'"
+            .parse::<Config>()
+            .unwrap();
+        assert_eq!(config.get_options(), Some("-c search_path=my\\ schema"));
+    }
+
     #[test]
     fn test_simple_parsing() {
         let s = "user=pass_user dbname=postgres host=host1,host2 hostaddr=127.0.0.1,127.0.0.2 port=26257";
@@ -1188,4 +1865,27 @@ mod tests {
         let s = "user=pass_user dbname=postgres host=host1 hostaddr=127.0.0 port=26257";
         s.parse::<Config>().err().unwrap();
     }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_keepalive_settings() {
+        use socket2::{Domain, Socket, TcpKeepalive, Type};
+        use std::time::Duration;
+
+        let s = "keepalives=1 keepalives_idle=15 keepalives_interval=5 keepalives_count=9";
+        let config = s.parse::<Config>().unwrap();
+        assert!(config.get_keepalives());
+
+        let socket = Socket::new(Domain::IPV4, Type::STREAM, None).unwrap();
+        socket
+            .set_tcp_keepalive(&TcpKeepalive::from(&config.keepalive_config))
+            .unwrap();
+        assert!(socket.keepalive().unwrap());
+        assert_eq!(socket.keepalive_time().unwrap(), Duration::from_secs(15));
+        assert_eq!(socket.keepalive_interval().unwrap(), Duration::from_secs(5));
+        assert_eq!(socket.keepalive_retries().unwrap(), 9);
+
+        let config = "keepalives=0 keepalives_idle=15".parse::<Config>().unwrap();
+        assert!(!config.get_keepalives());
+    }
 }
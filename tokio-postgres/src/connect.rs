@@ -1,19 +1,56 @@
 use crate::client::{Addr, SocketConfig};
-use crate::config::{Host, LoadBalanceHosts, TargetSessionAttrs};
+use crate::config::{Host, LoadBalanceHosts, RetryPolicy, SslMode, TargetSessionAttrs};
 use crate::connect_raw::connect_raw;
 use crate::connect_socket::connect_socket;
+use crate::error::SqlState;
+#[cfg(feature = "srv")]
+use crate::srv;
 use crate::tls::MakeTlsConnect;
 use crate::{Client, Config, Connection, Error, SimpleQueryMessage, Socket};
 use futures_util::{future, pin_mut, Future, FutureExt, Stream};
 use rand::seq::SliceRandom;
+use std::net::{IpAddr, SocketAddr};
 use std::task::Poll;
 use std::{cmp, io};
-use tokio::net;
+use tokio::{net, time};
 
 pub async fn connect<T>(
     mut tls: T,
     config: &Config,
 ) -> Result<(Client, Connection<Socket, T::Stream>), Error>
+where
+    T: MakeTlsConnect<Socket>,
+{
+    connect_inner(&mut tls, config).await
+}
+
+pub async fn connect_with_retry<T>(
+    mut tls: T,
+    config: &Config,
+    policy: RetryPolicy,
+) -> Result<(Client, Connection<Socket, T::Stream>), Error>
+where
+    T: MakeTlsConnect<Socket>,
+{
+    let mut backoff = policy.initial_backoff;
+    let mut attempt = 1;
+    loop {
+        match connect_inner(&mut tls, config).await {
+            Ok(r) => return Ok(r),
+            Err(e) if attempt < policy.max_attempts && e.is_retryable() => {
+                time::sleep(backoff).await;
+                backoff = cmp::min(backoff * 2, policy.max_backoff);
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+async fn connect_inner<T>(
+    tls: &mut T,
+    config: &Config,
+) -> Result<(Client, Connection<Socket, T::Stream>), Error>
 where
     T: MakeTlsConnect<Socket>,
 {
@@ -47,7 +84,7 @@ where
         indices.shuffle(&mut rand::thread_rng());
     }
 
-    let mut error = None;
+    let mut errors = vec![];
     for i in indices {
         let host = config.host.get(i);
         let hostaddr = config.hostaddr.get(i);
@@ -67,6 +104,41 @@ where
             None => None,
         };
 
+        #[cfg(feature = "srv")]
+        if config.srv_lookup && hostaddr.is_none() {
+            if let Some(Host::Tcp(host)) = host {
+                match srv::lookup(host, config.connect_timeout).await {
+                    Some(srv::SrvLookup::Targets(targets)) => {
+                        for target in targets {
+                            let hostname = Some(target.target.clone());
+                            match connect_host(
+                                Host::Tcp(target.target),
+                                hostname,
+                                target.port,
+                                tls,
+                                config,
+                            )
+                            .await
+                            {
+                                Ok((client, connection)) => return Ok((client, connection)),
+                                Err(e) => errors.extend(e),
+                            }
+                        }
+                        continue;
+                    }
+                    Some(srv::SrvLookup::Unavailable) => {
+                        let e = io::Error::new(
+                            io::ErrorKind::NotFound,
+                            "the host's SRV record says the service is not available",
+                        );
+                        errors.push(Error::connect(e).with_host(tcp_host(host, None, port)));
+                        continue;
+                    }
+                    None => {}
+                }
+            }
+        }
+
         // Try to use the value of hostaddr to establish the TCP connection,
         // fallback to host if hostaddr is not present.
         let addr = match hostaddr {
@@ -74,13 +146,13 @@ where
             None => host.cloned().unwrap(),
         };
 
-        match connect_host(addr, hostname, port, &mut tls, config).await {
+        match connect_host(addr, hostname, port, tls, config).await {
             Ok((client, connection)) => return Ok((client, connection)),
-            Err(e) => error = Some(e),
+            Err(e) => errors.extend(e),
         }
     }
 
-    Err(error.unwrap())
+    Err(Error::connect_attempts(errors))
 }
 
 async fn connect_host<T>(
@@ -89,48 +161,80 @@ async fn connect_host<T>(
     port: u16,
     tls: &mut T,
     config: &Config,
-) -> Result<(Client, Connection<Socket, T::Stream>), Error>
+) -> Result<(Client, Connection<Socket, T::Stream>), Vec<Error>>
 where
     T: MakeTlsConnect<Socket>,
 {
     match host {
         Host::Tcp(host) => {
-            let mut addrs = net::lookup_host((&*host, port))
-                .await
-                .map_err(Error::connect)?
-                .collect::<Vec<_>>();
+            let cached = config
+                .dns_cache_ttl
+                .and_then(|_| config.dns_cache.get(&host, port));
+            let from_cache = cached.is_some();
+            let mut addrs = match cached {
+                Some(addrs) => addrs,
+                None => {
+                    let addrs = net::lookup_host((&*host, port))
+                        .await
+                        .map_err(
+                            |e| vec![Error::connect(e).with_host(tcp_host(&host, None, port))],
+                        )?
+                        .collect::<Vec<_>>();
+                    if let Some(ttl) = config.dns_cache_ttl {
+                        config.dns_cache.insert(&host, port, addrs.clone(), ttl);
+                    }
+                    addrs
+                }
+            };
 
             if config.load_balance_hosts == LoadBalanceHosts::Random {
                 addrs.shuffle(&mut rand::thread_rng());
             }
 
-            let mut last_err = None;
+            let mut errors = vec![];
             for addr in addrs {
                 match connect_once(Addr::Tcp(addr.ip()), hostname.as_deref(), port, tls, config)
                     .await
                 {
                     Ok(stream) => return Ok(stream),
                     Err(e) => {
-                        last_err = Some(e);
+                        // errors from the server, like a mismatched `target_session_attrs`, mean the address is fine
+                        if from_cache && e.is_unreachable() {
+                            config.dns_cache.invalidate(&host, port);
+                        }
+                        errors.push(e.with_host(tcp_host(&host, Some(addr.ip()), port)));
                         continue;
                     }
                 };
             }
 
-            Err(last_err.unwrap_or_else(|| {
-                Error::connect(io::Error::new(
+            if errors.is_empty() {
+                let e = Error::connect(io::Error::new(
                     io::ErrorKind::InvalidInput,
                     "could not resolve any addresses",
-                ))
-            }))
+                ));
+                errors.push(e.with_host(tcp_host(&host, None, port)));
+            }
+            Err(errors)
         }
         #[cfg(unix)]
         Host::Unix(path) => {
-            connect_once(Addr::Unix(path), hostname.as_deref(), port, tls, config).await
+            let socket = path.join(format!(".s.PGSQL.{}", port));
+            connect_once(Addr::Unix(path), hostname.as_deref(), port, tls, config)
+                .await
+                .map_err(|e| vec![e.with_host(socket.display().to_string())])
         }
     }
 }
 
+fn tcp_host(host: &str, ip: Option<IpAddr>, port: u16) -> String {
+    match ip {
+        Some(ip) if ip.to_string() != host => format!("{} ({}):{}", host, ip, port),
+        Some(ip) => SocketAddr::new(ip, port).to_string(),
+        None => format!("{}:{}", host, port),
+    }
+}
+
 async fn connect_once<T>(
     addr: Addr,
     hostname: Option<&str>,
@@ -141,24 +245,19 @@ async fn connect_once<T>(
 where
     T: MakeTlsConnect<Socket>,
 {
-    let socket = connect_socket(
-        &addr,
-        port,
-        config.connect_timeout,
-        config.tcp_user_timeout,
-        if config.keepalives {
-            Some(&config.keepalive_config)
-        } else {
-            None
-        },
-    )
-    .await?;
-
-    let tls = tls
-        .make_tls_connect(hostname.unwrap_or(""))
-        .map_err(|e| Error::tls(e.into()))?;
-    let has_hostname = hostname.is_some();
-    let (mut client, mut connection) = connect_raw(socket, tls, has_hostname, config).await?;
+    let (mut client, mut connection) =
+        match connect_stream(&addr, hostname, port, tls, config).await {
+            // pg_hba.conf rejected the plaintext connection, so try again with TLS
+            Err(e)
+                if config.ssl_mode == SslMode::Allow
+                    && e.code() == Some(&SqlState::INVALID_AUTHORIZATION_SPECIFICATION) =>
+            {
+                let mut config = config.clone();
+                config.ssl_mode = SslMode::Require;
+                connect_stream(&addr, hostname, port, tls, &config).await?
+            }
+            r => r?,
+        };
 
     if config.target_session_attrs != TargetSessionAttrs::Any {
         let rows = client.simple_query_raw("SHOW transaction_read_only");
@@ -216,12 +315,54 @@ where
         port,
         connect_timeout: config.connect_timeout,
         tcp_user_timeout: config.tcp_user_timeout,
+        tcp_nodelay: config.tcp_nodelay,
+        require_peer: config.require_peer.clone(),
         keepalive: if config.keepalives {
             Some(config.keepalive_config.clone())
         } else {
             None
         },
+        socket_configurer: config.socket_configurer.clone(),
     });
 
     Ok((client, connection))
 }
+
+async fn connect_stream<T>(
+    addr: &Addr,
+    hostname: Option<&str>,
+    port: u16,
+    tls: &mut T,
+    config: &Config,
+) -> Result<(Client, Connection<Socket, T::Stream>), Error>
+where
+    T: MakeTlsConnect<Socket>,
+{
+    let socket = connect_socket(
+        addr,
+        port,
+        config.connect_timeout,
+        config.tcp_user_timeout,
+        config.tcp_nodelay,
+        config.require_peer.as_deref(),
+        if config.keepalives {
+            Some(&config.keepalive_config)
+        } else {
+            None
+        },
+    )
+    .await?;
+
+    if let Some(configurer) = &config.socket_configurer {
+        configurer.configure(&socket)?;
+    }
+
+    let tls = tls
+        .make_tls_connect_with_negotiation(
+            hostname.unwrap_or(""),
+            config.ssl_mode,
+            config.ssl_negotiation,
+        )
+        .map_err(|e| Error::tls(e.into()))?;
+    connect_raw(socket, tls, hostname.is_some(), config).await
+}
@@ -1,6 +1,7 @@
 use crate::codec::{BackendMessage, BackendMessages, FrontendMessage, PostgresCodec};
 use crate::config::{self, Config, ReplicationMode};
 use crate::connect_tls::connect_tls;
+use crate::connection_info::{AuthMethod, ConnectionInfo, Transport};
 use crate::maybe_tls_stream::MaybeTlsStream;
 use crate::tls::{TlsConnect, TlsStream};
 use crate::{Client, Connection, Error};
@@ -8,15 +9,19 @@ use bytes::BytesMut;
 use fallible_iterator::FallibleIterator;
 use futures_channel::mpsc;
 use futures_util::{ready, Sink, SinkExt, Stream, TryStreamExt};
+use parking_lot::Mutex;
 use postgres_protocol::authentication;
 use postgres_protocol::authentication::sasl;
 use postgres_protocol::authentication::sasl::ScramSha256;
-use postgres_protocol::message::backend::{AuthenticationSaslBody, Message};
+use postgres_protocol::message::backend::{
+    AuthenticationSaslBody, Message, NegotiateProtocolVersionBody,
+};
 use postgres_protocol::message::frontend;
 use std::borrow::Cow;
 use std::collections::{HashMap, VecDeque};
 use std::io;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_util::codec::Framed;
@@ -89,10 +94,29 @@ where
     S: AsyncRead + AsyncWrite + Unpin,
     T: TlsConnect<S>,
 {
-    let stream = connect_tls(stream, config.ssl_mode, tls, has_hostname).await?;
+    let stream = connect_tls(
+        stream,
+        config.ssl_mode,
+        config.ssl_negotiation,
+        tls,
+        has_hostname,
+    )
+    .await?;
+
+    let codec = PostgresCodec {
+        max_message_size: config.max_backend_message_size,
+        max_rows: config.row_buffer_limit,
+    };
+    let mut inner = match config.read_buffer_size {
+        Some(capacity) => Framed::with_capacity(stream, codec, capacity),
+        None => Framed::new(stream, codec),
+    };
+    if let Some(boundary) = config.write_buffer_size {
+        inner.set_backpressure_boundary(boundary);
+    }
 
     let mut stream = StartupStream {
-        inner: Framed::new(stream, PostgresCodec),
+        inner,
         buf: BackendMessages::empty(),
         delayed: VecDeque::new(),
     };
@@ -102,13 +126,52 @@ where
         .as_deref()
         .map_or_else(|| Cow::Owned(whoami::username()), Cow::Borrowed);
 
+    let transport = match stream.inner.get_ref() {
+        MaybeTlsStream::Raw(_) => Transport::Plaintext,
+        MaybeTlsStream::Tls(_) => Transport::Tls,
+    };
+
     startup(&mut stream, config, &user).await?;
-    authenticate(&mut stream, config, &user).await?;
+    let auth_method = authenticate(&mut stream, config, &user).await?;
     let (process_id, secret_key, parameters) = read_info(&mut stream).await?;
 
+    // Postgres 10 removed support for floating point timestamps, so they are rare enough that we don't decode them.
+    // Fail loudly rather than returning garbage from every timestamp column.
+    if parameters.get("integer_datetimes").map(|s| &**s) == Some("off") {
+        return Err(Error::config(
+            "servers using floating point datetimes (integer_datetimes=off) are unsupported".into(),
+        ));
+    }
+
     let (sender, receiver) = mpsc::unbounded();
-    let client = Client::new(sender, config.ssl_mode, process_id, secret_key);
-    let connection = Connection::new(stream.inner, stream.delayed, parameters, receiver);
+    let close_reason = Arc::new(Mutex::new(None));
+    let client = Client::new(
+        sender,
+        config,
+        process_id,
+        secret_key,
+        ConnectionInfo {
+            auth_method,
+            transport,
+        },
+        close_reason.clone(),
+    );
+    #[allow(unused_mut)]
+    let mut connection = Connection::new(
+        stream.inner,
+        stream.delayed,
+        parameters,
+        receiver,
+        close_reason,
+    );
+    #[cfg(feature = "runtime")]
+    if let Some(interval) = config.idle_ping_interval {
+        connection.set_idle_ping_interval(interval);
+    }
+    #[cfg(feature = "runtime")]
+    if let Some(timeout) = config.socket_read_timeout {
+        connection.set_read_timeout(timeout);
+    }
 
     Ok((client, connection))
 }
@@ -130,9 +193,16 @@ where
     if let Some(options) = &config.options {
         params.push(("options", &**options));
     }
-    if let Some(application_name) = &config.application_name {
+    if let Some(application_name) = config
+        .application_name
+        .as_ref()
+        .or(config.fallback_application_name.as_ref())
+    {
         params.push(("application_name", &**application_name));
     }
+    if config.default_transaction_read_only {
+        params.push(("default_transaction_read_only", "on"));
+    }
     if let Some(replication_mode) = &config.replication_mode {
         match replication_mode {
             ReplicationMode::Physical => params.push(("replication", "true")),
@@ -153,15 +223,23 @@ async fn authenticate<S, T>(
     stream: &mut StartupStream<S, T>,
     config: &Config,
     user: &str,
-) -> Result<(), Error>
+) -> Result<AuthMethod, Error>
 where
     S: AsyncRead + AsyncWrite + Unpin,
     T: TlsStream + Unpin,
 {
-    match stream.try_next().await.map_err(Error::io)? {
+    let message = match stream.try_next().await.map_err(Error::io)? {
+        Some(Message::NegotiateProtocolVersion(body)) => {
+            check_protocol_version(&body)?;
+            stream.try_next().await.map_err(Error::io)?
+        }
+        message => message,
+    };
+
+    let auth_method = match message {
         Some(Message::AuthenticationOk) => {
             can_skip_channel_binding(config)?;
-            return Ok(());
+            return Ok(AuthMethod::Trust);
         }
         Some(Message::AuthenticationCleartextPassword) => {
             can_skip_channel_binding(config)?;
@@ -172,6 +250,7 @@ where
                 .ok_or_else(|| Error::config("password missing".into()))?;
 
             authenticate_password(stream, pass).await?;
+            AuthMethod::Password
         }
         Some(Message::AuthenticationMd5Password(body)) => {
             can_skip_channel_binding(config)?;
@@ -183,10 +262,9 @@ where
 
             let output = authentication::md5_hash(user.as_bytes(), pass, body.salt());
             authenticate_password(stream, output.as_bytes()).await?;
+            AuthMethod::Md5
         }
-        Some(Message::AuthenticationSasl(body)) => {
-            authenticate_sasl(stream, body, config).await?;
-        }
+        Some(Message::AuthenticationSasl(body)) => authenticate_sasl(stream, body, config).await?,
         Some(Message::AuthenticationKerberosV5)
         | Some(Message::AuthenticationScmCredential)
         | Some(Message::AuthenticationGss)
@@ -198,16 +276,39 @@ where
         Some(Message::ErrorResponse(body)) => return Err(Error::db(body)),
         Some(_) => return Err(Error::unexpected_message()),
         None => return Err(Error::closed()),
-    }
+    };
 
     match stream.try_next().await.map_err(Error::io)? {
-        Some(Message::AuthenticationOk) => Ok(()),
+        Some(Message::AuthenticationOk) => Ok(auth_method),
         Some(Message::ErrorResponse(body)) => Err(Error::db(body)),
         Some(_) => Err(Error::unexpected_message()),
         None => Err(Error::closed()),
     }
 }
 
+// We request protocol 3.0 without any protocol options, so the server can't offer a lower version, and anything it
+// reports back as unsupported is a mismatch we can't resolve.
+fn check_protocol_version(body: &NegotiateProtocolVersionBody) -> Result<(), Error> {
+    let options = body.options().collect::<Vec<_>>().map_err(Error::parse)?;
+    if !options.is_empty() {
+        return Err(Error::protocol(
+            format!("unrecognized protocol options: {}", options.join(", ")).into(),
+        ));
+    }
+
+    if body.newest_minor_version() != 0 {
+        return Err(Error::protocol(
+            format!(
+                "requested protocol version 3.0, but the server offered 3.{}",
+                body.newest_minor_version()
+            )
+            .into(),
+        ));
+    }
+
+    Ok(())
+}
+
 fn can_skip_channel_binding(config: &Config) -> Result<(), Error> {
     match config.channel_binding {
         config::ChannelBinding::Disable | config::ChannelBinding::Prefer => Ok(()),
@@ -238,7 +339,7 @@ async fn authenticate_sasl<S, T>(
     stream: &mut StartupStream<S, T>,
     body: AuthenticationSaslBody,
     config: &Config,
-) -> Result<(), Error>
+) -> Result<AuthMethod, Error>
 where
     S: AsyncRead + AsyncWrite + Unpin,
     T: TlsStream + Unpin,
@@ -259,6 +360,20 @@ where
         }
     }
 
+    // a server can only offer channel binding over TLS, so offering it over a plain stream means either the stream
+    // was already wrapped in TLS before being passed in or something is tampering with the exchange. Channel binding
+    // isn't possible either way, so only fail if it's required.
+    if has_scram_plus {
+        if let MaybeTlsStream::Raw(_) = stream.inner.get_ref() {
+            if config.channel_binding == config::ChannelBinding::Require {
+                return Err(Error::authentication(
+                    "server offered SCRAM-SHA-256-PLUS over a non-TLS connection".into(),
+                ));
+            }
+            has_scram_plus = false;
+        }
+    }
+
     let channel_binding = stream
         .inner
         .get_ref()
@@ -286,6 +401,7 @@ where
     }
 
     let mut scram = ScramSha256::new(password, channel_binding);
+    scram.set_min_iterations(config.scram_min_iterations);
 
     let mut buf = BytesMut::new();
     frontend::sasl_initial_response(mechanism, scram.message(), &mut buf).map_err(Error::encode)?;
@@ -323,7 +439,11 @@ where
         .finish(body.data())
         .map_err(|e| Error::authentication(e.into()))?;
 
-    Ok(())
+    if mechanism == sasl::SCRAM_SHA_256_PLUS {
+        Ok(AuthMethod::ScramSha256Plus)
+    } else {
+        Ok(AuthMethod::ScramSha256)
+    }
 }
 
 async fn read_info<S, T>(
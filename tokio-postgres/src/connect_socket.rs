@@ -2,8 +2,14 @@ use crate::client::Addr;
 use crate::keepalive::KeepaliveConfig;
 use crate::{Error, Socket};
 use socket2::{SockRef, TcpKeepalive};
+#[cfg(unix)]
+use std::ffi::CStr;
 use std::future::Future;
 use std::io;
+#[cfg(unix)]
+use std::mem::MaybeUninit;
+#[cfg(unix)]
+use std::ptr;
 use std::time::Duration;
 use tokio::net::TcpStream;
 #[cfg(unix)]
@@ -17,6 +23,8 @@ pub(crate) async fn connect_socket(
     #[cfg_attr(not(target_os = "linux"), allow(unused_variables))] tcp_user_timeout: Option<
         Duration,
     >,
+    tcp_nodelay: bool,
+    #[cfg_attr(not(unix), allow(unused_variables))] require_peer: Option<&str>,
     keepalive_config: Option<&KeepaliveConfig>,
 ) -> Result<Socket, Error> {
     match addr {
@@ -24,7 +32,7 @@ pub(crate) async fn connect_socket(
             let stream =
                 connect_with_timeout(TcpStream::connect((*ip, port)), connect_timeout).await?;
 
-            stream.set_nodelay(true).map_err(Error::connect)?;
+            stream.set_nodelay(tcp_nodelay).map_err(Error::connect)?;
 
             let sock_ref = SockRef::from(&stream);
             #[cfg(target_os = "linux")]
@@ -46,11 +54,66 @@ pub(crate) async fn connect_socket(
         Addr::Unix(dir) => {
             let path = dir.join(format!(".s.PGSQL.{}", port));
             let socket = connect_with_timeout(UnixStream::connect(path), connect_timeout).await?;
+            if let Some(require_peer) = require_peer {
+                check_peer(&socket, require_peer)?;
+            }
             Ok(Socket::new_unix(socket))
         }
     }
 }
 
+#[cfg(unix)]
+fn check_peer(socket: &UnixStream, require_peer: &str) -> Result<(), Error> {
+    let uid = socket.peer_cred().map_err(Error::connect)?.uid();
+    let user = user_name(uid).map_err(Error::connect)?;
+    if user == require_peer {
+        Ok(())
+    } else {
+        Err(Error::connect(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "requirepeer specifies \"{}\", but actual peer user name is \"{}\"",
+                require_peer, user
+            ),
+        )))
+    }
+}
+
+#[cfg(unix)]
+fn user_name(uid: libc::uid_t) -> io::Result<String> {
+    let mut buf = vec![0 as libc::c_char; 1024];
+    loop {
+        let mut passwd = MaybeUninit::<libc::passwd>::uninit();
+        let mut result = ptr::null_mut();
+        // SAFETY: the pointers are valid for the duration of the call, and `buf.len()` is the size of `buf`.
+        let r = unsafe {
+            libc::getpwuid_r(
+                uid,
+                passwd.as_mut_ptr(),
+                buf.as_mut_ptr(),
+                buf.len(),
+                &mut result,
+            )
+        };
+        match r {
+            0 if result.is_null() => {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("local user with ID {} does not exist", uid),
+                ))
+            }
+            // SAFETY: on success `result` points to `passwd`, whose `pw_name` is a nul-terminated string in `buf`.
+            0 => {
+                return Ok(unsafe { CStr::from_ptr((*result).pw_name) }
+                    .to_string_lossy()
+                    .into_owned())
+            }
+            libc::ERANGE => buf.resize(buf.len() * 2, 0),
+            r => return Err(io::Error::from_raw_os_error(r)),
+        }
+    }
+}
+
 async fn connect_with_timeout<F, T>(connect: F, timeout: Option<Duration>) -> Result<T, Error>
 where
     F: Future<Output = io::Result<T>>,
@@ -70,3 +133,35 @@ where
         },
     }
 }
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn check_peer_user() {
+        let (socket, _peer) = UnixStream::pair().unwrap();
+        // SAFETY: getuid has no preconditions and always succeeds.
+        let user = user_name(unsafe { libc::getuid() }).unwrap();
+        assert!(!user.is_empty());
+        check_peer(&socket, &user).unwrap();
+
+        let err = check_peer(&socket, "tokio_postgres_no_such_user")
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string().contains(&format!(
+                "requirepeer specifies \"tokio_postgres_no_such_user\", but actual peer user name is \"{}\"",
+                user
+            )),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn unknown_uid() {
+        let err = user_name(libc::uid_t::MAX - 1).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::config::SslMode;
+use crate::config::{SslMode, SslNegotiation};
 use crate::maybe_tls_stream::MaybeTlsStream;
 use crate::tls::private::ForcePrivateApi;
 use crate::tls::TlsConnect;
@@ -10,6 +10,7 @@ use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 pub async fn connect_tls<S, T>(
     mut stream: S,
     mode: SslMode,
+    negotiation: SslNegotiation,
     tls: T,
     has_hostname: bool,
 ) -> Result<MaybeTlsStream<S, T::Stream>, Error>
@@ -18,26 +19,37 @@ where
     T: TlsConnect<S>,
 {
     match mode {
-        SslMode::Disable => return Ok(MaybeTlsStream::Raw(stream)),
+        SslMode::Disable | SslMode::Allow => return Ok(MaybeTlsStream::Raw(stream)),
         SslMode::Prefer if !tls.can_connect(ForcePrivateApi) => {
             return Ok(MaybeTlsStream::Raw(stream))
         }
-        SslMode::Prefer | SslMode::Require => {}
+        _ => {}
     }
 
-    let mut buf = BytesMut::new();
-    frontend::ssl_request(&mut buf);
-    stream.write_all(&buf).await.map_err(Error::io)?;
+    match negotiation {
+        SslNegotiation::Postgres => {
+            let mut buf = BytesMut::new();
+            frontend::ssl_request(&mut buf);
+            stream.write_all(&buf).await.map_err(Error::io)?;
 
-    let mut buf = [0];
-    stream.read_exact(&mut buf).await.map_err(Error::io)?;
+            let mut buf = [0];
+            stream.read_exact(&mut buf).await.map_err(Error::io)?;
 
-    if buf[0] != b'S' {
-        if SslMode::Require == mode {
-            return Err(Error::tls("server does not support TLS".into()));
-        } else {
-            return Ok(MaybeTlsStream::Raw(stream));
+            if buf[0] != b'S' {
+                if mode.requires_tls() {
+                    return Err(Error::tls("server does not support TLS".into()));
+                } else {
+                    return Ok(MaybeTlsStream::Raw(stream));
+                }
+            }
         }
+        // there's no way to fall back to plaintext once the handshake has been sent
+        SslNegotiation::Direct if !mode.requires_tls() => {
+            return Err(Error::config(
+                "sslnegotiation=direct requires sslmode=require, verify-ca or verify-full".into(),
+            ))
+        }
+        SslNegotiation::Direct => {}
     }
 
     if !has_hostname {
@@ -1,36 +1,72 @@
 use crate::codec::{BackendMessage, BackendMessages, FrontendMessage, PostgresCodec};
 use crate::copy_both::CopyBothReceiver;
 use crate::copy_in::CopyInReceiver;
-use crate::error::DbError;
+use crate::error::{DbError, Severity};
 use crate::maybe_tls_stream::MaybeTlsStream;
+use crate::streaming_param::StreamingParamReceiver;
 use crate::{AsyncMessage, Error, Notification};
 use bytes::BytesMut;
 use fallible_iterator::FallibleIterator;
 use futures_channel::mpsc;
 use futures_util::{ready, stream::FusedStream, Sink, Stream, StreamExt};
 use log::{info, trace};
+use parking_lot::Mutex;
 use postgres_protocol::message::backend::Message;
 use postgres_protocol::message::frontend;
 use std::collections::{HashMap, VecDeque};
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
+#[cfg(feature = "runtime")]
+use std::time::{Duration, Instant};
 use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::OwnedSemaphorePermit;
+#[cfg(feature = "runtime")]
+use tokio::time;
 use tokio_util::codec::Framed;
 
 pub enum RequestMessages {
     Single(FrontendMessage),
     CopyIn(CopyInReceiver),
     CopyBoth(CopyBothReceiver),
+    StreamingParam(StreamingParamReceiver),
 }
 
+/// Collects the notices received while a request is being responded to.
+pub type NoticeSink = Arc<Mutex<Vec<DbError>>>;
+
 pub struct Request {
     pub messages: RequestMessages,
     pub sender: mpsc::Sender<BackendMessages>,
+    pub notices: Option<NoticeSink>,
+    pub permit: Option<OwnedSemaphorePermit>,
+    /// Whether the messages come from `Client::send_raw`, in which case the connection is closed if they start a
+    /// `COPY` since nothing can complete it.
+    pub raw: bool,
 }
 
 pub struct Response {
     sender: mpsc::Sender<BackendMessages>,
+    notices: Option<NoticeSink>,
+    // frees a slot in the client's pipeline when the request completes
+    _permit: Option<OwnedSemaphorePermit>,
+    raw: bool,
+}
+
+#[cfg(feature = "runtime")]
+struct IdlePing {
+    interval: Duration,
+    sleep: Pin<Box<time::Sleep>>,
+    // the receiving half is dropped, so the responses to pings are discarded
+    sender: mpsc::Sender<BackendMessages>,
+}
+
+#[cfg(feature = "runtime")]
+struct ReadTimeout {
+    timeout: Duration,
+    // reset whenever a message is sent or received
+    sleep: Pin<Box<time::Sleep>>,
 }
 
 #[derive(PartialEq, Debug)]
@@ -56,6 +92,11 @@ pub struct Connection<S, T> {
     pending_responses: VecDeque<BackendMessage>,
     responses: VecDeque<Response>,
     state: State,
+    close_reason: Arc<Mutex<Option<DbError>>>,
+    #[cfg(feature = "runtime")]
+    idle_ping: Option<IdlePing>,
+    #[cfg(feature = "runtime")]
+    read_timeout: Option<ReadTimeout>,
 }
 
 impl<S, T> Connection<S, T>
@@ -68,6 +109,7 @@ where
         pending_responses: VecDeque<BackendMessage>,
         parameters: HashMap<String, String>,
         receiver: mpsc::UnboundedReceiver<Request>,
+        close_reason: Arc<Mutex<Option<DbError>>>,
     ) -> Connection<S, T> {
         Connection {
             stream,
@@ -77,7 +119,72 @@ where
             pending_responses,
             responses: VecDeque::new(),
             state: State::Active,
+            close_reason,
+            #[cfg(feature = "runtime")]
+            idle_ping: None,
+            #[cfg(feature = "runtime")]
+            read_timeout: None,
+        }
+    }
+
+    #[cfg(feature = "runtime")]
+    pub(crate) fn set_idle_ping_interval(&mut self, interval: Duration) {
+        self.idle_ping = Some(IdlePing {
+            interval,
+            sleep: Box::pin(time::sleep(interval)),
+            sender: mpsc::channel(0).0,
+        });
+    }
+
+    #[cfg(feature = "runtime")]
+    pub(crate) fn set_read_timeout(&mut self, timeout: Duration) {
+        self.read_timeout = Some(ReadTimeout {
+            timeout,
+            sleep: Box::pin(time::sleep(timeout)),
+        });
+    }
+
+    #[cfg(feature = "runtime")]
+    fn reset_read_timeout(&mut self) {
+        if let Some(read_timeout) = &mut self.read_timeout {
+            let deadline = Instant::now() + read_timeout.timeout;
+            read_timeout.sleep.as_mut().reset(deadline.into());
+        }
+    }
+
+    /// Returns an error if the server has sent nothing for the read timeout while a response is outstanding.
+    ///
+    /// Time spent waiting on the client, such as for the data of a `COPY ... FROM STDIN` or for a response's receiver to
+    /// accept more messages, doesn't count.
+    #[cfg(feature = "runtime")]
+    fn poll_read_timeout(&mut self, cx: &mut Context<'_>) -> Result<(), Error> {
+        let read_timeout = match &mut self.read_timeout {
+            Some(read_timeout) => read_timeout,
+            None => return Ok(()),
+        };
+
+        if self.responses.is_empty()
+            || self.pending_request.is_some()
+            || !self.pending_responses.is_empty()
+        {
+            return Ok(());
+        }
+
+        if read_timeout.sleep.as_mut().poll(cx).is_ready() {
+            trace!("poll_read: timed out waiting on response");
+            return Err(Error::__private_api_timeout());
         }
+
+        Ok(())
+    }
+
+    fn start_send(&mut self, message: FrontendMessage) -> Result<(), Error> {
+        Pin::new(&mut self.stream)
+            .start_send(message)
+            .map_err(Error::io)?;
+        #[cfg(feature = "runtime")]
+        self.reset_read_timeout();
+        Ok(())
     }
 
     fn poll_response(
@@ -109,10 +216,16 @@ where
                     return Ok(None);
                 }
             };
+            #[cfg(feature = "runtime")]
+            self.reset_read_timeout();
 
             let (mut messages, request_complete) = match message {
                 BackendMessage::Async(Message::NoticeResponse(body)) => {
                     let error = DbError::parse(&mut body.fields()).map_err(Error::parse)?;
+                    // the server responds to requests in order, so the notice belongs to the one at the front
+                    if let Some(notices) = self.responses.front().and_then(|r| r.notices.as_ref()) {
+                        notices.lock().push(error.clone());
+                    }
                     return Ok(Some(AsyncMessage::Notice(error)));
                 }
                 BackendMessage::Async(Message::NotificationResponse(body)) => {
@@ -140,11 +253,22 @@ where
             let mut response = match self.responses.pop_front() {
                 Some(response) => response,
                 None => match messages.next().map_err(Error::parse)? {
-                    Some(Message::ErrorResponse(error)) => return Err(Error::db(error)),
+                    Some(Message::ErrorResponse(error)) => {
+                        let error = Error::db(error);
+                        // the server is about to close the connection; remember why so the client can report it
+                        if let Some(e) = error.as_db_error() {
+                            if let Some(Severity::Fatal | Severity::Panic) = e.parsed_severity() {
+                                *self.close_reason.lock() = Some(e.clone());
+                            }
+                        }
+                        return Err(error);
+                    }
                     _ => return Err(Error::unexpected_message()),
                 },
             };
 
+            let starts_copy = response.raw && messages.starts_copy();
+
             match response.sender.poll_ready(cx) {
                 Poll::Ready(Ok(())) => {
                     let _ = response.sender.start_send(messages);
@@ -168,6 +292,12 @@ where
                     return Ok(None);
                 }
             }
+
+            // the session waits on COPY data the client will never send, or sends COPY data nothing will read
+            if starts_copy {
+                trace!("poll_read: raw messages started a COPY, closing");
+                return Err(Error::unexpected_message());
+            }
         }
     }
 
@@ -184,8 +314,16 @@ where
         match self.receiver.poll_next_unpin(cx) {
             Poll::Ready(Some(request)) => {
                 trace!("polled new request");
+                #[cfg(feature = "runtime")]
+                if let Some(idle_ping) = &mut self.idle_ping {
+                    let deadline = Instant::now() + idle_ping.interval;
+                    idle_ping.sleep.as_mut().reset(deadline.into());
+                }
                 self.responses.push_back(Response {
                     sender: request.sender,
+                    notices: request.notices,
+                    _permit: request.permit,
+                    raw: request.raw,
                 });
                 Poll::Ready(Some(request.messages))
             }
@@ -227,6 +365,10 @@ where
                     return Ok(true);
                 }
                 Poll::Pending => {
+                    #[cfg(feature = "runtime")]
+                    if self.poll_idle_ping(cx)? {
+                        continue;
+                    }
                     trace!("poll_write: waiting on request");
                     return Ok(true);
                 }
@@ -234,9 +376,7 @@ where
 
             match request {
                 RequestMessages::Single(request) => {
-                    Pin::new(&mut self.stream)
-                        .start_send(request)
-                        .map_err(Error::io)?;
+                    self.start_send(request)?;
                     if self.state == State::Terminating {
                         trace!("poll_write: sent eof, closing");
                         self.state = State::Closing;
@@ -255,9 +395,7 @@ where
                             return Ok(true);
                         }
                     };
-                    Pin::new(&mut self.stream)
-                        .start_send(message)
-                        .map_err(Error::io)?;
+                    self.start_send(message)?;
                     self.pending_request = Some(RequestMessages::CopyIn(receiver));
                 }
                 RequestMessages::CopyBoth(mut receiver) => {
@@ -273,15 +411,69 @@ where
                             return Ok(true);
                         }
                     };
-                    Pin::new(&mut self.stream)
-                        .start_send(message)
-                        .map_err(Error::io)?;
+                    self.start_send(message)?;
                     self.pending_request = Some(RequestMessages::CopyBoth(receiver));
                 }
+                RequestMessages::StreamingParam(mut receiver) => {
+                    let message = match receiver.poll_next_unpin(cx) {
+                        Poll::Ready(Some(message)) => message,
+                        Poll::Ready(None) => {
+                            trace!("poll_write: finished streaming_param request");
+                            continue;
+                        }
+                        Poll::Pending => {
+                            trace!("poll_write: waiting on streaming_param reader");
+                            self.pending_request = Some(RequestMessages::StreamingParam(receiver));
+                            return Ok(true);
+                        }
+                    };
+                    self.start_send(message)?;
+                    self.pending_request = Some(RequestMessages::StreamingParam(receiver));
+                }
             }
         }
     }
 
+    #[cfg(feature = "runtime")]
+    fn poll_idle_ping(&mut self, cx: &mut Context<'_>) -> Result<bool, Error> {
+        let idle_ping = match &mut self.idle_ping {
+            Some(idle_ping) => idle_ping,
+            None => return Ok(false),
+        };
+
+        if idle_ping.sleep.as_mut().poll(cx).is_pending() {
+            return Ok(false);
+        }
+        let deadline = Instant::now() + idle_ping.interval;
+        idle_ping.sleep.as_mut().reset(deadline.into());
+
+        if self
+            .responses
+            .iter()
+            .any(|r| r.sender.same_receiver(&idle_ping.sender))
+        {
+            trace!("poll_write: idle ping timed out");
+            return Err(Error::__private_api_timeout());
+        }
+
+        if !self.responses.is_empty() {
+            return Ok(false);
+        }
+
+        trace!("poll_write: sending idle ping");
+        let sender = idle_ping.sender.clone();
+        let mut request = BytesMut::new();
+        frontend::sync(&mut request);
+        self.start_send(FrontendMessage::Raw(request.freeze()))?;
+        self.responses.push_back(Response {
+            sender,
+            notices: None,
+            _permit: None,
+            raw: false,
+        });
+        Ok(true)
+    }
+
     fn poll_flush(&mut self, cx: &mut Context<'_>) -> Result<(), Error> {
         match Pin::new(&mut self.stream)
             .poll_flush(cx)
@@ -334,6 +526,9 @@ where
         if want_flush {
             self.poll_flush(cx)?;
         }
+        // checked last, since sending a request restarts the timeout
+        #[cfg(feature = "runtime")]
+        self.poll_read_timeout(cx)?;
         match message {
             Some(message) => Poll::Ready(Some(Ok(message))),
             None => match self.poll_shutdown(cx) {
@@ -0,0 +1,44 @@
+/// The authentication method negotiated with the server.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AuthMethod {
+    /// The server accepted the connection without requesting any credentials.
+    Trust,
+    /// A cleartext password.
+    Password,
+    /// An MD5-hashed password.
+    Md5,
+    /// SCRAM-SHA-256 without channel binding.
+    ScramSha256,
+    /// SCRAM-SHA-256 with channel binding to the TLS session.
+    ScramSha256Plus,
+}
+
+/// The transport used to communicate with the server.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Transport {
+    /// The connection is not encrypted.
+    Plaintext,
+    /// The connection is encrypted with TLS.
+    Tls,
+}
+
+/// Information about how a connection was established, recorded during the handshake.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ConnectionInfo {
+    pub(crate) auth_method: AuthMethod,
+    pub(crate) transport: Transport,
+}
+
+impl ConnectionInfo {
+    /// Returns the authentication method used when connecting.
+    pub fn auth_method(&self) -> AuthMethod {
+        self.auth_method
+    }
+
+    /// Returns the transport used by the connection.
+    pub fn transport(&self) -> Transport {
+        self.transport
+    }
+}
@@ -232,7 +232,7 @@ where
 
     let (mut sender, receiver) = mpsc::channel(1);
     let receiver = CopyBothReceiver::new(receiver);
-    let mut responses = client.send(RequestMessages::CopyBoth(receiver))?;
+    let mut responses = client.send(RequestMessages::CopyBoth(receiver)).await?;
 
     sender
         .send(CopyBothMessage::Message(FrontendMessage::Raw(buf)))
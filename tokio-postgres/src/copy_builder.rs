@@ -0,0 +1,280 @@
+use crate::binary_copy::{BinaryCopyInWriter, BinaryCopyOutStream};
+use crate::types::Type;
+use crate::{Client, CopyInSink, CopyOutStream, Error};
+use bytes::{Buf, Bytes};
+use postgres_protocol::escape::{escape_identifier, escape_literal};
+use std::fmt::Write;
+
+/// The format of the data transferred by a `COPY` query.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CopyFormat {
+    /// Postgres's text format, with one line per row and columns separated by a delimiter character.
+    Text,
+    /// Comma-separated values.
+    Csv,
+    /// Postgres's binary format. This is what `BinaryCopyInWriter` and `BinaryCopyOutStream` read and write.
+    Binary,
+}
+
+#[derive(Debug, Clone)]
+struct CopyOptions {
+    schema: Option<String>,
+    table: String,
+    columns: Vec<String>,
+    format: Option<CopyFormat>,
+    delimiter: Option<char>,
+    header: Option<bool>,
+    null: Option<String>,
+    quote: Option<char>,
+    escape: Option<char>,
+}
+
+impl CopyOptions {
+    fn new(table: &str) -> CopyOptions {
+        CopyOptions {
+            schema: None,
+            table: table.to_string(),
+            columns: vec![],
+            format: None,
+            delimiter: None,
+            header: None,
+            null: None,
+            quote: None,
+            escape: None,
+        }
+    }
+
+    fn query(&self, direction: &str) -> String {
+        let mut query = "COPY ".to_string();
+        if let Some(schema) = &self.schema {
+            query.push_str(&escape_identifier(schema));
+            query.push('.');
+        }
+        query.push_str(&escape_identifier(&self.table));
+
+        if !self.columns.is_empty() {
+            query.push_str(" (");
+            for (i, column) in self.columns.iter().enumerate() {
+                if i > 0 {
+                    query.push_str(", ");
+                }
+                query.push_str(&escape_identifier(column));
+            }
+            query.push(')');
+        }
+
+        query.push(' ');
+        query.push_str(direction);
+
+        let mut options = vec![];
+        if let Some(format) = self.format {
+            let format = match format {
+                CopyFormat::Text => "text",
+                CopyFormat::Csv => "csv",
+                CopyFormat::Binary => "binary",
+            };
+            options.push(format!("FORMAT {}", format));
+        }
+        if let Some(delimiter) = self.delimiter {
+            options.push(format!(
+                "DELIMITER {}",
+                escape_literal(&delimiter.to_string())
+            ));
+        }
+        if let Some(header) = self.header {
+            options.push(format!("HEADER {}", header));
+        }
+        if let Some(null) = &self.null {
+            options.push(format!("NULL {}", escape_literal(null)));
+        }
+        if let Some(quote) = self.quote {
+            options.push(format!("QUOTE {}", escape_literal(&quote.to_string())));
+        }
+        if let Some(escape) = self.escape {
+            options.push(format!("ESCAPE {}", escape_literal(&escape.to_string())));
+        }
+
+        if !options.is_empty() {
+            let _ = write!(query, " ({})", options.join(", "));
+        }
+
+        query
+    }
+}
+
+macro_rules! copy_option_methods {
+    () => {
+        /// Sets the schema containing the table.
+        ///
+        /// By default, the table is looked up using the session's `search_path`.
+        pub fn schema(mut self, schema: &str) -> Self {
+            self.options.schema = Some(schema.to_string());
+            self
+        }
+
+        /// Sets the columns to copy, in the order their values appear in the data.
+        ///
+        /// By default, all of the table's columns are copied in the order they were defined.
+        pub fn columns(mut self, columns: &[&str]) -> Self {
+            self.options.columns = columns.iter().map(|c| c.to_string()).collect();
+            self
+        }
+
+        /// Sets the format of the data.
+        ///
+        /// Defaults to `CopyFormat::Text`.
+        pub fn format(mut self, format: CopyFormat) -> Self {
+            self.options.format = Some(format);
+            self
+        }
+
+        /// Sets the character separating the columns of a row.
+        ///
+        /// Defaults to a tab in the text format, and a comma in the CSV format. Not allowed in the binary format.
+        pub fn delimiter(mut self, delimiter: char) -> Self {
+            self.options.delimiter = Some(delimiter);
+            self
+        }
+
+        /// Sets whether the data starts with a header line containing the column names.
+        ///
+        /// Defaults to `false`. Only allowed in the CSV format, and the text format on Postgres 15 and newer.
+        pub fn header(mut self, header: bool) -> Self {
+            self.options.header = Some(header);
+            self
+        }
+
+        /// Sets the string representing a null value.
+        ///
+        /// Defaults to `\N` in the text format and an unquoted empty string in the CSV format. Not allowed in the
+        /// binary format.
+        pub fn null(mut self, null: &str) -> Self {
+            self.options.null = Some(null.to_string());
+            self
+        }
+
+        /// Sets the character used to quote values.
+        ///
+        /// Defaults to a double quote. Only allowed in the CSV format.
+        pub fn quote(mut self, quote: char) -> Self {
+            self.options.quote = Some(quote);
+            self
+        }
+
+        /// Sets the character used to escape the quote character within quoted values.
+        ///
+        /// Defaults to the quote character. Only allowed in the CSV format.
+        pub fn escape(mut self, escape: char) -> Self {
+            self.options.escape = Some(escape);
+            self
+        }
+    };
+}
+
+/// A builder for `COPY ... FROM STDIN` queries.
+///
+/// The table and column names are quoted as identifiers and the options are quoted as literals, so none of them are
+/// interpreted as SQL. Options that aren't valid for the selected format are rejected by the server when the copy
+/// starts.
+pub struct CopyInBuilder<'a> {
+    client: &'a Client,
+    options: CopyOptions,
+}
+
+impl<'a> CopyInBuilder<'a> {
+    pub(crate) fn new(client: &'a Client, table: &str) -> CopyInBuilder<'a> {
+        CopyInBuilder {
+            client,
+            options: CopyOptions::new(table),
+        }
+    }
+
+    copy_option_methods!();
+
+    /// Starts the copy, returning a sink to write the data to in the configured format.
+    pub async fn start<U>(self) -> Result<CopyInSink<U>, Error>
+    where
+        U: Buf + 'static + Send,
+    {
+        let query = self.options.query("FROM STDIN");
+        self.client.copy_in_simple(&query).await
+    }
+
+    /// Starts a binary format copy, returning a writer which encodes rows of values of the specified types.
+    ///
+    /// The format set with the `format` method is ignored.
+    pub async fn start_binary(mut self, types: &[Type]) -> Result<BinaryCopyInWriter, Error> {
+        self.options.format = Some(CopyFormat::Binary);
+        let sink = self.start::<Bytes>().await?;
+        Ok(BinaryCopyInWriter::new(sink, types))
+    }
+}
+
+/// A builder for `COPY ... TO STDOUT` queries.
+///
+/// The table and column names are quoted as identifiers and the options are quoted as literals, so none of them are
+/// interpreted as SQL. Options that aren't valid for the selected format are rejected by the server when the copy
+/// starts.
+pub struct CopyOutBuilder<'a> {
+    client: &'a Client,
+    options: CopyOptions,
+}
+
+impl<'a> CopyOutBuilder<'a> {
+    pub(crate) fn new(client: &'a Client, table: &str) -> CopyOutBuilder<'a> {
+        CopyOutBuilder {
+            client,
+            options: CopyOptions::new(table),
+        }
+    }
+
+    copy_option_methods!();
+
+    /// Starts the copy, returning a stream of the data in the configured format.
+    pub async fn start(self) -> Result<CopyOutStream, Error> {
+        let query = self.options.query("TO STDOUT");
+        self.client.copy_out_simple(&query).await
+    }
+
+    /// Starts a binary format copy, returning a stream which decodes rows of values of the specified types.
+    ///
+    /// The format set with the `format` method is ignored.
+    pub async fn start_binary(mut self, types: &[Type]) -> Result<BinaryCopyOutStream, Error> {
+        self.options.format = Some(CopyFormat::Binary);
+        let stream = self.start().await?;
+        Ok(BinaryCopyOutStream::new(stream, types))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn plain() {
+        assert_eq!(
+            CopyOptions::new("foo").query("FROM STDIN"),
+            "COPY \"foo\" FROM STDIN"
+        );
+    }
+
+    #[test]
+    fn quoting() {
+        let mut options = CopyOptions::new("my \"table\"");
+        options.schema = Some("public".to_string());
+        options.columns = vec!["a".to_string(), "B; DROP TABLE x".to_string()];
+        options.format = Some(CopyFormat::Csv);
+        options.delimiter = Some('\'');
+        options.header = Some(true);
+        options.null = Some("\\N".to_string());
+        options.quote = Some('"');
+        options.escape = Some('\\');
+
+        assert_eq!(
+            options.query("TO STDOUT"),
+            "COPY \"public\".\"my \"\"table\"\"\" (\"a\", \"B; DROP TABLE x\") TO STDOUT \
+             (FORMAT csv, DELIMITER '''', HEADER true, NULL  E'\\\\N', QUOTE '\"', ESCAPE  E'\\\\')"
+        );
+    }
+}
@@ -22,16 +22,25 @@ enum CopyInMessage {
 
 pub struct CopyInReceiver {
     receiver: mpsc::Receiver<CopyInMessage>,
+    simple: bool,
     done: bool,
 }
 
 impl CopyInReceiver {
-    fn new(receiver: mpsc::Receiver<CopyInMessage>) -> CopyInReceiver {
+    fn new(receiver: mpsc::Receiver<CopyInMessage>, simple: bool) -> CopyInReceiver {
         CopyInReceiver {
             receiver,
+            simple,
             done: false,
         }
     }
+
+    // a simple query is already followed by a ReadyForQuery once the copy ends, so a Sync would produce a second one
+    fn sync(&self, buf: &mut BytesMut) {
+        if !self.simple {
+            frontend::sync(buf);
+        }
+    }
 }
 
 impl Stream for CopyInReceiver {
@@ -48,14 +57,14 @@ impl Stream for CopyInReceiver {
                 self.done = true;
                 let mut buf = BytesMut::new();
                 frontend::copy_done(&mut buf);
-                frontend::sync(&mut buf);
+                self.sync(&mut buf);
                 Poll::Ready(Some(FrontendMessage::Raw(buf.freeze())))
             }
             None => {
                 self.done = true;
                 let mut buf = BytesMut::new();
                 frontend::copy_fail("", &mut buf).unwrap();
-                frontend::sync(&mut buf);
+                self.sync(&mut buf);
                 Poll::Ready(Some(FrontendMessage::Raw(buf.freeze())))
             }
         }
@@ -66,6 +75,7 @@ enum SinkState {
     Active,
     Closing,
     Reading,
+    Finished(u64),
 }
 
 pin_project! {
@@ -73,6 +83,10 @@ pin_project! {
     ///
     /// The copy *must* be explicitly completed via the `Sink::close` or `finish` methods. If it is
     /// not, the copy will be aborted.
+    ///
+    /// Closing the sink sends the end of the data and waits for the server to complete the copy, so a stream of data
+    /// can be copied in with `StreamExt::forward`. Since the sink is `!Unpin`, pin it and forward into `as_mut()` to
+    /// be able to call `finish` afterwards, which returns the number of rows copied once the copy is complete.
     pub struct CopyInSink<T> {
         #[pin]
         sender: mpsc::Sender<CopyInMessage>,
@@ -112,11 +126,13 @@ where
                     match ready!(this.responses.poll_next(cx))? {
                         Message::CommandComplete(body) => {
                             let rows = extract_row_affected(&body)?;
+                            *this.state = SinkState::Finished(rows);
                             return Poll::Ready(Ok(rows));
                         }
                         _ => return Poll::Ready(Err(Error::unexpected_message())),
                     }
                 }
+                SinkState::Finished(rows) => return Poll::Ready(Ok(rows)),
             }
         }
     }
@@ -193,8 +209,8 @@ where
     T: Buf + 'static + Send,
 {
     let (mut sender, receiver) = mpsc::channel(1);
-    let receiver = CopyInReceiver::new(receiver);
-    let mut responses = client.send(RequestMessages::CopyIn(receiver))?;
+    let receiver = CopyInReceiver::new(receiver, simple);
+    let mut responses = client.send(RequestMessages::CopyIn(receiver)).await?;
 
     sender
         .send(CopyInMessage::Message(FrontendMessage::Raw(buf)))
@@ -202,9 +218,12 @@ where
         .map_err(|_| Error::closed())?;
 
     if !simple {
-        match responses.next().await? {
-            Message::BindComplete => {}
-            _ => return Err(Error::unexpected_message()),
+        loop {
+            match responses.next().await? {
+                Message::ParseComplete => {}
+                Message::BindComplete => break,
+                _ => return Err(Error::unexpected_message()),
+            }
         }
     }
 
@@ -227,6 +246,7 @@ pub async fn copy_in<T>(client: &InnerClient, statement: Statement) -> Result<Co
 where
     T: Buf + 'static + Send,
 {
+    let statement = statement.current();
     debug!("executing copy in statement {}", statement.name());
 
     let buf = query::encode(client, &statement, slice_iter(&[]))?;
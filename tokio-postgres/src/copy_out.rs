@@ -23,6 +23,7 @@ pub async fn copy_out_simple(client: &InnerClient, query: &str) -> Result<CopyOu
 }
 
 pub async fn copy_out(client: &InnerClient, statement: Statement) -> Result<CopyOutStream, Error> {
+    let statement = statement.current();
     debug!("executing copy out statement {}", statement.name());
 
     let buf = query::encode(client, &statement, slice_iter(&[]))?;
@@ -34,12 +35,17 @@ pub async fn copy_out(client: &InnerClient, statement: Statement) -> Result<Copy
 }
 
 async fn start(client: &InnerClient, buf: Bytes, simple: bool) -> Result<Responses, Error> {
-    let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+    let mut responses = client
+        .send(RequestMessages::Single(FrontendMessage::Raw(buf)))
+        .await?;
 
     if !simple {
-        match responses.next().await? {
-            Message::BindComplete => {}
-            _ => return Err(Error::unexpected_message()),
+        loop {
+            match responses.next().await? {
+                Message::ParseComplete => {}
+                Message::BindComplete => break,
+                _ => return Err(Error::unexpected_message()),
+            }
         }
     }
 
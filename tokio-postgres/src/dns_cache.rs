@@ -0,0 +1,72 @@
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+struct Entry {
+    addrs: Vec<SocketAddr>,
+    expires: Instant,
+}
+
+/// A cache of host name resolutions, shared by clones of a `Config`.
+#[derive(Clone, Default)]
+pub(crate) struct DnsCache(Arc<Mutex<HashMap<(String, u16), Entry>>>);
+
+impl DnsCache {
+    pub fn get(&self, host: &str, port: u16) -> Option<Vec<SocketAddr>> {
+        let mut entries = self.0.lock();
+        let key = (host.to_string(), port);
+        match entries.get(&key) {
+            Some(entry) if entry.expires > Instant::now() => Some(entry.addrs.clone()),
+            Some(_) => {
+                entries.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&self, host: &str, port: u16, addrs: Vec<SocketAddr>, ttl: Duration) {
+        let entry = Entry {
+            addrs,
+            expires: Instant::now() + ttl,
+        };
+        self.0.lock().insert((host.to_string(), port), entry);
+    }
+
+    pub fn invalidate(&self, host: &str, port: u16) {
+        self.0.lock().remove(&(host.to_string(), port));
+    }
+}
+
+// The cache is runtime state rather than configuration, so it doesn't take part in comparisons between `Config`s.
+impl PartialEq for DnsCache {
+    fn eq(&self, _: &DnsCache) -> bool {
+        true
+    }
+}
+
+impl Eq for DnsCache {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn expiry_and_invalidation() {
+        let cache = DnsCache::default();
+        let addrs = vec!["127.0.0.1:5432".parse().unwrap()];
+
+        cache.insert("localhost", 5432, addrs.clone(), Duration::from_secs(60));
+        assert_eq!(cache.get("localhost", 5432), Some(addrs.clone()));
+        assert_eq!(cache.get("localhost", 5433), None);
+
+        // clones share the cache
+        cache.clone().invalidate("localhost", 5432);
+        assert_eq!(cache.get("localhost", 5432), None);
+
+        cache.insert("localhost", 5432, addrs, Duration::ZERO);
+        assert_eq!(cache.get("localhost", 5432), None);
+    }
+}
@@ -356,11 +356,15 @@ enum Kind {
     #[cfg(feature = "runtime")]
     Connect,
     Timeout,
+    Protocol,
 }
 
 struct ErrorInner {
     kind: Kind,
     cause: Option<Box<dyn error::Error + Sync + Send>>,
+    host: Option<String>,
+    statement_index: Option<usize>,
+    previous_attempts: Vec<Error>,
 }
 
 /// An error communicating with the Postgres server.
@@ -371,6 +375,9 @@ impl fmt::Debug for Error {
         fmt.debug_struct("Error")
             .field("kind", &self.0.kind)
             .field("cause", &self.0.cause)
+            .field("host", &self.0.host)
+            .field("statement_index", &self.0.statement_index)
+            .field("previous_attempts", &self.0.previous_attempts)
             .finish()
     }
 }
@@ -398,7 +405,14 @@ impl fmt::Display for Error {
             #[cfg(feature = "runtime")]
             Kind::Connect => fmt.write_str("error connecting to server")?,
             Kind::Timeout => fmt.write_str("timeout waiting for server")?,
+            Kind::Protocol => fmt.write_str("server does not support the requested protocol")?,
         };
+        if let Some(ref host) = self.0.host {
+            write!(fmt, " at {}", host)?;
+        }
+        if let Some(idx) = self.0.statement_index {
+            write!(fmt, " preparing statement {}", idx)?;
+        }
         if let Some(ref cause) = self.0.cause {
             write!(fmt, ": {}", cause)?;
         }
@@ -437,14 +451,126 @@ impl Error {
         self.as_db_error().map(DbError::code)
     }
 
+    /// Returns the index of the statement which failed to prepare, if the error was returned by
+    /// [`Client::prepare_batch`](crate::Client::prepare_batch).
+    ///
+    /// This is set for every error returned by `prepare_batch`. Errors which aren't caused by a particular
+    /// statement, like the connection closing before the batch was sent, have the index of the first statement which
+    /// wasn't prepared.
+    pub fn statement_index(&self) -> Option<usize> {
+        self.0.statement_index
+    }
+
+    /// Returns the number of parameters the statement expects and the number which were provided, if the error was
+    /// caused by a mismatch between them.
+    ///
+    /// The counts are checked before the parameters are sent to the server, including when a query string is executed
+    /// directly rather than prepared first.
+    pub fn parameter_counts(&self) -> Option<(usize, usize)> {
+        match self.0.kind {
+            Kind::Parameters(real, expected) => Some((expected, real)),
+            _ => None,
+        }
+    }
+
+    /// Determines if the error is likely to be transient, such that retrying the operation may succeed.
+    ///
+    /// This is the case for network-level failures like a refused or reset connection or a timeout, and for server
+    /// errors indicating a shutdown, a server which is still starting up or in recovery, connection exhaustion,
+    /// serialization failure, or deadlock. Errors such as authentication failures or an invalid configuration are not
+    /// retryable.
+    pub fn is_retryable(&self) -> bool {
+        match self.0.kind {
+            Kind::Closed | Kind::Timeout => true,
+            #[cfg(feature = "runtime")]
+            Kind::Connect => self.is_transient_io(),
+            Kind::Io => self.is_transient_io(),
+            Kind::Db => matches!(
+                self.code(),
+                Some(
+                    &SqlState::ADMIN_SHUTDOWN
+                        | &SqlState::CRASH_SHUTDOWN
+                        | &SqlState::CANNOT_CONNECT_NOW
+                        | &SqlState::TOO_MANY_CONNECTIONS
+                        | &SqlState::CONNECTION_EXCEPTION
+                        | &SqlState::CONNECTION_FAILURE
+                        | &SqlState::SQLCLIENT_UNABLE_TO_ESTABLISH_SQLCONNECTION
+                        | &SqlState::T_R_SERIALIZATION_FAILURE
+                        | &SqlState::T_R_DEADLOCK_DETECTED
+                )
+            ),
+            _ => false,
+        }
+    }
+
+    fn is_transient_io(&self) -> bool {
+        match self.source().and_then(|e| e.downcast_ref::<io::Error>()) {
+            Some(e) => matches!(
+                e.kind(),
+                io::ErrorKind::ConnectionRefused
+                    | io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::NotConnected
+                    | io::ErrorKind::AddrNotAvailable
+                    | io::ErrorKind::BrokenPipe
+                    | io::ErrorKind::TimedOut
+                    | io::ErrorKind::Interrupted
+                    | io::ErrorKind::UnexpectedEof
+            ),
+            None => false,
+        }
+    }
+
+    /// Returns the server a connection attempt was being made to when the error occurred, if it occurred while
+    /// connecting.
+    ///
+    /// This is formatted as `host:port` for TCP connections, followed by the IP address in parentheses if the host is
+    /// a name, and as the socket path for Unix socket connections.
+    pub fn host(&self) -> Option<&str> {
+        self.0.host.as_deref()
+    }
+
+    /// Returns the errors of the connection attempts which failed before this one.
+    ///
+    /// When every host and address in the configuration fails, the error of the final attempt is returned and the
+    /// others can be found here, in the order they were made. This is empty for errors not returned by a connect
+    /// method.
+    pub fn previous_attempts(&self) -> &[Error] {
+        &self.0.previous_attempts
+    }
+
     fn new(kind: Kind, cause: Option<Box<dyn error::Error + Sync + Send>>) -> Error {
-        Error(Box::new(ErrorInner { kind, cause }))
+        Error(Box::new(ErrorInner {
+            kind,
+            cause,
+            host: None,
+            statement_index: None,
+            previous_attempts: vec![],
+        }))
+    }
+
+    #[cfg(feature = "runtime")]
+    pub(crate) fn with_host(mut self, host: String) -> Error {
+        self.0.host = Some(host);
+        self
+    }
+
+    /// Combines the errors of every failed connection attempt, in the order they were made.
+    #[cfg(feature = "runtime")]
+    pub(crate) fn connect_attempts(mut errors: Vec<Error>) -> Error {
+        let mut error = errors.pop().expect("no connection attempts were made");
+        error.0.previous_attempts = errors;
+        error
     }
 
     pub(crate) fn closed() -> Error {
         Error::new(Kind::Closed, None)
     }
 
+    pub(crate) fn closed_by_server(e: DbError) -> Error {
+        Error::new(Kind::Closed, Some(Box::new(e)))
+    }
+
     pub(crate) fn unexpected_message() -> Error {
         Error::new(Kind::UnexpectedMessage, None)
     }
@@ -482,6 +608,11 @@ impl Error {
         Error::new(Kind::Parameters(real, expected), None)
     }
 
+    pub(crate) fn with_statement_index(mut self, idx: usize) -> Error {
+        self.0.statement_index = Some(idx);
+        self
+    }
+
     pub(crate) fn tls(e: Box<dyn error::Error + Sync + Send>) -> Error {
         Error::new(Kind::Tls, Some(e))
     }
@@ -511,6 +642,38 @@ impl Error {
         Error::new(Kind::Connect, Some(Box::new(e)))
     }
 
+    pub(crate) fn protocol(e: Box<dyn error::Error + Sync + Send>) -> Error {
+        Error::new(Kind::Protocol, Some(e))
+    }
+
+    /// Determines if the error means the server couldn't be reached or stopped responding, rather than that it
+    /// refused the connection.
+    #[cfg(feature = "runtime")]
+    pub(crate) fn is_unreachable(&self) -> bool {
+        match self.0.kind {
+            Kind::Io | Kind::Timeout => true,
+            Kind::Connect => self
+                .0
+                .cause
+                .as_ref()
+                .and_then(|e| e.downcast_ref::<io::Error>())
+                .is_some_and(|e| {
+                    matches!(
+                        e.kind(),
+                        io::ErrorKind::TimedOut
+                            | io::ErrorKind::ConnectionRefused
+                            | io::ErrorKind::ConnectionReset
+                            | io::ErrorKind::ConnectionAborted
+                            | io::ErrorKind::NotConnected
+                            | io::ErrorKind::AddrNotAvailable
+                            | io::ErrorKind::HostUnreachable
+                            | io::ErrorKind::NetworkUnreachable
+                    )
+                }),
+            _ => false,
+        }
+    }
+
     #[doc(hidden)]
     pub fn __private_api_timeout() -> Error {
         Error::new(Kind::Timeout, None)
@@ -1,7 +1,14 @@
+use crate::copy_out::CopyOutStream;
+use crate::error::DbError;
 use crate::query::RowStream;
 use crate::types::{BorrowToSql, ToSql, Type};
-use crate::{Client, Error, Row, Statement, ToStatement, Transaction};
+use crate::{
+    CancelToken, Client, CopyInBuilder, CopyInSink, CopyOutBuilder, Error, Row, SessionInfo,
+    SimpleQueryMessage, Statement, StreamingParam, ToStatement, Transaction,
+};
 use async_trait::async_trait;
+use bytes::Buf;
+use tokio::io::AsyncRead;
 
 mod private {
     pub trait Sealed {}
@@ -10,6 +17,13 @@ mod private {
 /// A trait allowing abstraction over connections and transactions.
 ///
 /// This trait is "sealed", and cannot be implemented outside of this crate.
+///
+/// A few methods of `Client` are left out, and can be called on the [`client`](GenericClient::client) instead:
+///
+/// * `query_deadline` and `cancel_query` take a TLS connector, and the futures of this trait's methods must be `Send`,
+///   which would require bounds on the connector that `Client` doesn't.
+/// * `build_transaction` sets options, such as the isolation level, which only apply to a top level transaction,
+///   while `transaction` on a `Transaction` creates a savepoint.
 #[async_trait]
 pub trait GenericClient: private::Sealed {
     /// Like `Client::execute`.
@@ -66,12 +80,70 @@ pub trait GenericClient: private::Sealed {
         parameter_types: &[Type],
     ) -> Result<Statement, Error>;
 
+    /// Like `Client::prepare_batch`.
+    async fn prepare_batch(&self, queries: &[&str]) -> Result<Vec<Statement>, Error>;
+
+    /// Like `Client::query_with_notices`.
+    async fn query_with_notices<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Row>, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send;
+
+    /// Like `Client::execute_with_notices`.
+    async fn execute_with_notices<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(u64, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send;
+
+    /// Like `Client::execute_streaming`.
+    async fn execute_streaming<T, R>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+        value: StreamingParam<R>,
+    ) -> Result<u64, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+        R: AsyncRead + Send + 'static;
+
+    /// Like `Client::copy_in`.
+    async fn copy_in<T, U>(&self, statement: &T) -> Result<CopyInSink<U>, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+        U: Buf + 'static + Send;
+
+    /// Like `Client::copy_out`.
+    async fn copy_out<T>(&self, statement: &T) -> Result<CopyOutStream, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send;
+
+    /// Like `Client::build_copy_in`.
+    fn build_copy_in(&self, table: &str) -> CopyInBuilder<'_>;
+
+    /// Like `Client::build_copy_out`.
+    fn build_copy_out(&self, table: &str) -> CopyOutBuilder<'_>;
+
+    /// Like `Client::simple_query`.
+    async fn simple_query(&self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error>;
+
     /// Like `Client::transaction`.
     async fn transaction(&mut self) -> Result<Transaction<'_>, Error>;
 
     /// Like `Client::batch_execute`.
     async fn batch_execute(&self, query: &str) -> Result<(), Error>;
 
+    /// Like `Client::session_info`.
+    async fn session_info(&self) -> Result<SessionInfo, Error>;
+
+    /// Like `Client::cancel_token`.
+    fn cancel_token(&self) -> CancelToken;
+
     /// Returns a reference to the underlying `Client`.
     fn client(&self) -> &Client;
 }
@@ -148,6 +220,72 @@ impl GenericClient for Client {
         self.prepare_typed(query, parameter_types).await
     }
 
+    async fn prepare_batch(&self, queries: &[&str]) -> Result<Vec<Statement>, Error> {
+        self.prepare_batch(queries).await
+    }
+
+    async fn query_with_notices<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Row>, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        self.query_with_notices(statement, params).await
+    }
+
+    async fn execute_with_notices<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(u64, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        self.execute_with_notices(statement, params).await
+    }
+
+    async fn execute_streaming<T, R>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+        value: StreamingParam<R>,
+    ) -> Result<u64, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+        R: AsyncRead + Send + 'static,
+    {
+        self.execute_streaming(statement, params, value).await
+    }
+
+    async fn copy_in<T, U>(&self, statement: &T) -> Result<CopyInSink<U>, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+        U: Buf + 'static + Send,
+    {
+        self.copy_in(statement).await
+    }
+
+    async fn copy_out<T>(&self, statement: &T) -> Result<CopyOutStream, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        self.copy_out(statement).await
+    }
+
+    fn build_copy_in(&self, table: &str) -> CopyInBuilder<'_> {
+        self.build_copy_in(table)
+    }
+
+    fn build_copy_out(&self, table: &str) -> CopyOutBuilder<'_> {
+        self.build_copy_out(table)
+    }
+
+    async fn simple_query(&self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error> {
+        self.simple_query(query).await
+    }
+
     async fn transaction(&mut self) -> Result<Transaction<'_>, Error> {
         self.transaction().await
     }
@@ -156,6 +294,14 @@ impl GenericClient for Client {
         self.batch_execute(query).await
     }
 
+    async fn session_info(&self) -> Result<SessionInfo, Error> {
+        self.session_info().await
+    }
+
+    fn cancel_token(&self) -> CancelToken {
+        self.cancel_token()
+    }
+
     fn client(&self) -> &Client {
         self
     }
@@ -234,6 +380,72 @@ impl GenericClient for Transaction<'_> {
         self.prepare_typed(query, parameter_types).await
     }
 
+    async fn prepare_batch(&self, queries: &[&str]) -> Result<Vec<Statement>, Error> {
+        self.prepare_batch(queries).await
+    }
+
+    async fn query_with_notices<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Row>, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        self.query_with_notices(statement, params).await
+    }
+
+    async fn execute_with_notices<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(u64, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        self.execute_with_notices(statement, params).await
+    }
+
+    async fn execute_streaming<T, R>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+        value: StreamingParam<R>,
+    ) -> Result<u64, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+        R: AsyncRead + Send + 'static,
+    {
+        self.execute_streaming(statement, params, value).await
+    }
+
+    async fn copy_in<T, U>(&self, statement: &T) -> Result<CopyInSink<U>, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+        U: Buf + 'static + Send,
+    {
+        self.copy_in(statement).await
+    }
+
+    async fn copy_out<T>(&self, statement: &T) -> Result<CopyOutStream, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        self.copy_out(statement).await
+    }
+
+    fn build_copy_in(&self, table: &str) -> CopyInBuilder<'_> {
+        self.build_copy_in(table)
+    }
+
+    fn build_copy_out(&self, table: &str) -> CopyOutBuilder<'_> {
+        self.build_copy_out(table)
+    }
+
+    async fn simple_query(&self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error> {
+        self.simple_query(query).await
+    }
+
     #[allow(clippy::needless_lifetimes)]
     async fn transaction<'a>(&'a mut self) -> Result<Transaction<'a>, Error> {
         self.transaction().await
@@ -243,6 +455,14 @@ impl GenericClient for Transaction<'_> {
         self.batch_execute(query).await
     }
 
+    async fn session_info(&self) -> Result<SessionInfo, Error> {
+        self.session_info().await
+    }
+
+    fn cancel_token(&self) -> CancelToken {
+        self.cancel_token()
+    }
+
     fn client(&self) -> &Client {
         self.client()
     }
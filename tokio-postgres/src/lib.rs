@@ -122,19 +122,24 @@ pub use crate::cancel_token::CancelToken;
 pub use crate::client::Client;
 pub use crate::config::Config;
 pub use crate::connection::Connection;
+pub use crate::connection_info::{AuthMethod, ConnectionInfo, Transport};
 pub use crate::copy_both::CopyBothDuplex;
+pub use crate::copy_builder::{CopyFormat, CopyInBuilder, CopyOutBuilder};
 pub use crate::copy_in::CopyInSink;
 pub use crate::copy_out::CopyOutStream;
 use crate::error::DbError;
 pub use crate::error::Error;
 pub use crate::generic_client::GenericClient;
 pub use crate::portal::Portal;
-pub use crate::query::RowStream;
+pub use crate::query::{QueryTimings, RowStream};
+pub use crate::raw_message::RawMessageStream;
 pub use crate::row::{Row, SimpleQueryRow};
-pub use crate::simple_query::SimpleQueryStream;
+pub use crate::session_info::SessionInfo;
+pub use crate::simple_query::{SimpleColumn, SimpleQueryStream};
 #[cfg(feature = "runtime")]
 pub use crate::socket::Socket;
 pub use crate::statement::{Column, Statement};
+pub use crate::streaming_param::StreamingParam;
 #[cfg(feature = "runtime")]
 use crate::tls::MakeTlsConnect;
 pub use crate::tls::NoTls;
@@ -142,6 +147,7 @@ pub use crate::to_statement::ToStatement;
 pub use crate::transaction::Transaction;
 pub use crate::transaction_builder::{IsolationLevel, TransactionBuilder};
 use crate::types::ToSql;
+use std::sync::Arc;
 
 pub mod binary_copy;
 mod bind;
@@ -159,9 +165,13 @@ mod connect_raw;
 mod connect_socket;
 mod connect_tls;
 mod connection;
+mod connection_info;
 mod copy_both;
+mod copy_builder;
 mod copy_in;
 mod copy_out;
+#[cfg(feature = "runtime")]
+mod dns_cache;
 pub mod error;
 mod generic_client;
 #[cfg(not(target_arch = "wasm32"))]
@@ -170,12 +180,17 @@ mod maybe_tls_stream;
 mod portal;
 mod prepare;
 mod query;
+mod raw_message;
 pub mod replication;
 pub mod row;
+mod session_info;
 mod simple_query;
 #[cfg(feature = "runtime")]
 mod socket;
+#[cfg(feature = "srv")]
+mod srv;
 mod statement;
+mod streaming_param;
 pub mod tls;
 mod to_statement;
 mod transaction;
@@ -251,6 +266,11 @@ pub enum SimpleQueryMessage {
     ///
     /// The number of rows modified or selected is returned.
     CommandComplete(u64),
+    /// The columns of the rows returned by the next statement in the query.
+    ///
+    /// This starts each result set, so a query running several `SELECT`s produces one for each, followed by its rows
+    /// and a `CommandComplete`. It is sent even if no rows are returned.
+    RowDescription(Arc<[SimpleColumn]>),
 }
 
 fn slice_iter<'a>(
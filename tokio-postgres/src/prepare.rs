@@ -1,4 +1,4 @@
-use crate::client::InnerClient;
+use crate::client::{InnerClient, Responses};
 use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
 use crate::error::SqlState;
@@ -9,7 +9,7 @@ use bytes::Bytes;
 use fallible_iterator::FallibleIterator;
 use futures_util::{pin_mut, TryStreamExt};
 use log::debug;
-use postgres_protocol::message::backend::Message;
+use postgres_protocol::message::backend::{Message, ParameterDescriptionBody, RowDescriptionBody};
 use postgres_protocol::message::frontend;
 use std::future::Future;
 use std::pin::Pin;
@@ -63,10 +63,91 @@ pub async fn prepare(
     query: &str,
     types: &[Type],
 ) -> Result<Statement, Error> {
-    let name = format!("s{}", NEXT_ID.fetch_add(1, Ordering::SeqCst));
+    let name = next_name(client);
     let buf = encode(client, &name, query, types)?;
-    let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+    let mut responses = client
+        .send(RequestMessages::Single(FrontendMessage::Raw(buf)))
+        .await?;
+
+    let (parameter_description, row_description) = read_description(&mut responses).await?;
+    build_statement(client, name, query, parameter_description, row_description).await
+}
+
+pub async fn prepare_batch(
+    client: &Arc<InnerClient>,
+    queries: &[&str],
+) -> Result<Vec<Statement>, Error> {
+    let names = queries
+        .iter()
+        .map(|_| next_name(client))
+        .collect::<Vec<_>>();
+    let buf = client.with_buf(|buf| {
+        for (idx, (name, query)) in names.iter().zip(queries).enumerate() {
+            debug!("preparing query {}: {}", name, query);
+            frontend::parse(name, query, None, buf)
+                .and_then(|()| frontend::describe(b'S', name, buf))
+                .map_err(|e| Error::encode(e).with_statement_index(idx))?;
+        }
+        frontend::sync(buf);
+        Ok(buf.split().freeze())
+    })?;
+    let mut responses = client
+        .send(RequestMessages::Single(FrontendMessage::Raw(buf)))
+        .await
+        .map_err(|e| e.with_statement_index(0))?;
+
+    // The server skips everything after the first failure, so only the statements before it need to be cleaned up.
+    let mut descriptions = Vec::with_capacity(queries.len());
+    for idx in 0..queries.len() {
+        match read_description(&mut responses).await {
+            Ok(description) => descriptions.push(description),
+            Err(e) => {
+                close(client, &names[..idx]);
+                return Err(e.with_statement_index(idx));
+            }
+        }
+    }
+
+    let mut statements = Vec::with_capacity(queries.len());
+    for (idx, ((name, query), (parameter_description, row_description))) in
+        names.iter().zip(queries).zip(descriptions).enumerate()
+    {
+        match build_statement(
+            client,
+            name.clone(),
+            query,
+            parameter_description,
+            row_description,
+        )
+        .await
+        {
+            Ok(statement) => statements.push(statement),
+            Err(e) => {
+                close(client, &names[idx..]);
+                return Err(e.with_statement_index(idx));
+            }
+        }
+    }
+
+    Ok(statements)
+}
+
+fn next_name(client: &InnerClient) -> String {
+    // statements are parsed into the unnamed statement just before each use instead
+    if client.pgbouncer_mode() {
+        return String::new();
+    }
+
+    format!(
+        "{}s{}",
+        client.statement_name_prefix(),
+        NEXT_ID.fetch_add(1, Ordering::SeqCst)
+    )
+}
 
+async fn read_description(
+    responses: &mut Responses,
+) -> Result<(ParameterDescriptionBody, Option<RowDescriptionBody>), Error> {
     match responses.next().await? {
         Message::ParseComplete => {}
         _ => return Err(Error::unexpected_message()),
@@ -83,6 +164,16 @@ pub async fn prepare(
         _ => return Err(Error::unexpected_message()),
     };
 
+    Ok((parameter_description, row_description))
+}
+
+async fn build_statement(
+    client: &Arc<InnerClient>,
+    name: String,
+    query: &str,
+    parameter_description: ParameterDescriptionBody,
+    row_description: Option<RowDescriptionBody>,
+) -> Result<Statement, Error> {
     let mut parameters = vec![];
     let mut it = parameter_description.parameters();
     while let Some(oid) = it.next().map_err(Error::parse)? {
@@ -99,16 +190,51 @@ pub async fn prepare(
                 name: field.name().to_string(),
                 table_oid: Some(field.table_oid()).filter(|n| *n != 0),
                 column_id: Some(field.column_id()).filter(|n| *n != 0),
+                type_modifier: Some(field.type_modifier()).filter(|n| *n != -1),
                 r#type: type_,
             };
             columns.push(column);
         }
     }
 
-    Ok(Statement::new(client, name, parameters, columns))
+    if name.is_empty() {
+        Ok(Statement::unnamed(
+            client,
+            query.to_string(),
+            parameters,
+            columns,
+        ))
+    } else {
+        Ok(Statement::new(
+            client,
+            name,
+            query.to_string(),
+            parameters,
+            columns,
+        ))
+    }
+}
+
+fn close(client: &InnerClient, names: &[String]) {
+    let names = names
+        .iter()
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>();
+    if names.is_empty() {
+        return;
+    }
+
+    let buf = client.with_buf(|buf| {
+        for name in names {
+            frontend::close(b'S', name, buf).unwrap();
+        }
+        frontend::sync(buf);
+        buf.split().freeze()
+    });
+    let _ = client.send_nowait(RequestMessages::Single(FrontendMessage::Raw(buf)));
 }
 
-fn prepare_rec<'a>(
+pub fn prepare_rec<'a>(
     client: &'a Arc<InnerClient>,
     query: &'a str,
     types: &'a [Type],
@@ -140,9 +266,19 @@ async fn get_type(client: &Arc<InnerClient>, oid: Oid) -> Result<Type, Error> {
         return Ok(type_);
     }
 
+    if client.pgbouncer_mode() {
+        return Err(Error::config(
+            format!(
+                "type with OID {} must be registered with `Client::register_types` in pgbouncer mode",
+                oid
+            )
+            .into(),
+        ));
+    }
+
     let stmt = typeinfo_statement(client).await?;
 
-    let rows = query::query(client, stmt, slice_iter(&[&oid])).await?;
+    let rows = query::query(client, stmt, slice_iter(&[&oid]), None).await?;
     pin_mut!(rows);
 
     let row = match rows.try_next().await? {
@@ -212,7 +348,7 @@ async fn typeinfo_statement(client: &Arc<InnerClient>) -> Result<Statement, Erro
 async fn get_enum_variants(client: &Arc<InnerClient>, oid: Oid) -> Result<Vec<String>, Error> {
     let stmt = typeinfo_enum_statement(client).await?;
 
-    query::query(client, stmt, slice_iter(&[&oid]))
+    query::query(client, stmt, slice_iter(&[&oid]), None)
         .await?
         .and_then(|row| async move { row.try_get(0) })
         .try_collect()
@@ -239,7 +375,7 @@ async fn typeinfo_enum_statement(client: &Arc<InnerClient>) -> Result<Statement,
 async fn get_composite_fields(client: &Arc<InnerClient>, oid: Oid) -> Result<Vec<Field>, Error> {
     let stmt = typeinfo_composite_statement(client).await?;
 
-    let rows = query::query(client, stmt, slice_iter(&[&oid]))
+    let rows = query::query(client, stmt, slice_iter(&[&oid]), None)
         .await?
         .try_collect::<Vec<_>>()
         .await?;
@@ -1,9 +1,11 @@
 use crate::client::{InnerClient, Responses};
 use crate::codec::FrontendMessage;
-use crate::connection::RequestMessages;
-use crate::types::{BorrowToSql, IsNull};
-use crate::{Error, Portal, Row, Statement};
-use bytes::{Bytes, BytesMut};
+use crate::connection::{NoticeSink, RequestMessages};
+use crate::error::SqlState;
+use crate::types::{BorrowToSql, IsNull, Type};
+use crate::{prepare, Error, Portal, Row, Statement};
+use byteorder::{BigEndian, ByteOrder};
+use bytes::{BufMut, Bytes, BytesMut};
 use futures_util::{ready, Stream};
 use log::{debug, log_enabled, Level};
 use pin_project_lite::pin_project;
@@ -12,7 +14,9 @@ use postgres_protocol::message::frontend;
 use std::fmt;
 use std::marker::PhantomPinned;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Instant;
 
 struct BorrowToSqlParamsDebug<'a, T>(&'a [T]);
 
@@ -28,31 +32,36 @@ where
 }
 
 pub async fn query<P, I>(
-    client: &InnerClient,
+    client: &Arc<InnerClient>,
     statement: Statement,
     params: I,
+    notices: Option<&NoticeSink>,
 ) -> Result<RowStream, Error>
 where
     P: BorrowToSql,
     I: IntoIterator<Item = P>,
     I::IntoIter: ExactSizeIterator,
 {
+    let timings = QueryTimings::new();
+    let current = statement.current();
     let buf = if log_enabled!(Level::Debug) {
         let params = params.into_iter().collect::<Vec<_>>();
         debug!(
             "executing statement {} with parameters: {:?}",
-            statement.name(),
+            current.name(),
             BorrowToSqlParamsDebug(params.as_slice()),
         );
-        encode(client, &statement, params)?
+        encode(client, &current, params)?
     } else {
-        encode(client, &statement, params)?
+        encode(client, &current, params)?
     };
-    let responses = start(client, buf).await?;
+    let (statement, responses) = start_statement(client, &statement, current, buf, notices).await?;
     Ok(RowStream {
         statement,
         responses,
         rows_affected: None,
+        empty_query: false,
+        timings,
         _p: PhantomPinned,
     })
 }
@@ -62,18 +71,23 @@ pub async fn query_portal(
     portal: &Portal,
     max_rows: i32,
 ) -> Result<RowStream, Error> {
+    let timings = QueryTimings::new();
     let buf = client.with_buf(|buf| {
         frontend::execute(portal.name(), max_rows, buf).map_err(Error::encode)?;
         frontend::sync(buf);
         Ok(buf.split().freeze())
     })?;
 
-    let responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+    let responses = client
+        .send(RequestMessages::Single(FrontendMessage::Raw(buf)))
+        .await?;
 
     Ok(RowStream {
         statement: portal.statement().clone(),
         responses,
         rows_affected: None,
+        empty_query: false,
+        timings,
         _p: PhantomPinned,
     })
 }
@@ -92,27 +106,29 @@ pub fn extract_row_affected(body: &CommandCompleteBody) -> Result<u64, Error> {
 }
 
 pub async fn execute<P, I>(
-    client: &InnerClient,
+    client: &Arc<InnerClient>,
     statement: Statement,
     params: I,
+    notices: Option<&NoticeSink>,
 ) -> Result<u64, Error>
 where
     P: BorrowToSql,
     I: IntoIterator<Item = P>,
     I::IntoIter: ExactSizeIterator,
 {
+    let current = statement.current();
     let buf = if log_enabled!(Level::Debug) {
         let params = params.into_iter().collect::<Vec<_>>();
         debug!(
             "executing statement {} with parameters: {:?}",
-            statement.name(),
+            current.name(),
             BorrowToSqlParamsDebug(params.as_slice()),
         );
-        encode(client, &statement, params)?
+        encode(client, &current, params)?
     } else {
-        encode(client, &statement, params)?
+        encode(client, &current, params)?
     };
-    let mut responses = start(client, buf).await?;
+    let (_, mut responses) = start_statement(client, &statement, current, buf, notices).await?;
 
     let mut rows = 0;
     loop {
@@ -128,14 +144,112 @@ where
     }
 }
 
-async fn start(client: &InnerClient, buf: Bytes) -> Result<Responses, Error> {
-    let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+/// Starts executing a statement, preparing it again and retrying once if the server reports that it doesn't exist, or
+/// with `auto_reprepare` that its result type changed.
+///
+/// `buf` holds the messages executing `current`, which is what `statement` currently resolves to. Returns the
+/// statement that was executed, which is the replacement if it was prepared again.
+async fn start_statement(
+    client: &Arc<InnerClient>,
+    statement: &Statement,
+    current: Statement,
+    buf: Bytes,
+    notices: Option<&NoticeSink>,
+) -> Result<(Statement, Responses), Error> {
+    let mut responses = client
+        .send_with_notices(
+            RequestMessages::Single(FrontendMessage::Raw(buf.clone())),
+            notices.cloned(),
+        )
+        .await?;
+
+    let error = match bind_complete(&mut responses).await {
+        Ok(()) => return Ok((current, responses)),
+        Err(e) => e,
+    };
+    if current.is_unnamed() || !(is_missing(&error) || client.auto_reprepare() && is_stale(&error))
+    {
+        return Err(error);
+    }
+
+    // The failure aborts any transaction in progress, and retrying would then just fail again.
+    loop {
+        if let Message::ReadyForQuery(body) = responses.next().await? {
+            if body.status() != b'I' {
+                return Err(error);
+            }
+            break;
+        }
+    }
+    // Requests pipelined behind this one have already been sent, so retrying it would run it after them instead.
+    if client.sent_after(&responses) {
+        return Err(error);
+    }
+
+    debug!("preparing statement {} again: {}", current.name(), error);
+    let replacement = prepare::prepare_rec(client, current.query(), current.params()).await?;
+    statement.set_replacement(replacement.clone());
+
+    let buf = rename_statement(&buf, replacement.name());
+    let responses = start(client, buf, notices).await?;
+    Ok((replacement, responses))
+}
+
+fn is_missing(error: &Error) -> bool {
+    error.code() == Some(&SqlState::INVALID_SQL_STATEMENT_NAME)
+}
+
+/// Determines if the error reports that a statement's result type changed since it was prepared.
+///
+/// The message is translated according to the server's `lc_messages`, so this relies on the function which raised
+/// the error instead.
+fn is_stale(error: &Error) -> bool {
+    error.as_db_error().is_some_and(|e| {
+        *e.code() == SqlState::FEATURE_NOT_SUPPORTED && e.routine() == Some("RevalidateCachedQuery")
+    })
+}
+
+/// Replaces the statement name in the `Bind` message at the start of `buf`.
+///
+/// The replacement is prepared with the same parameter types, so the rest of the messages can be reused as is rather
+/// than encoding the parameters again.
+fn rename_statement(buf: &[u8], name: &str) -> Bytes {
+    let portal_end = 5 + buf[5..].iter().position(|b| *b == 0).unwrap();
+    let name_end = portal_end + 1 + buf[portal_end + 1..].iter().position(|b| *b == 0).unwrap();
+    let old_len = BigEndian::read_i32(&buf[1..]);
+    let new_len = old_len + name.len() as i32 - (name_end - portal_end - 1) as i32;
+
+    let mut new = BytesMut::with_capacity(buf.len() + name.len());
+    new.put_u8(buf[0]);
+    new.put_i32(new_len);
+    new.put_slice(&buf[5..=portal_end]);
+    new.put_slice(name.as_bytes());
+    new.put_slice(&buf[name_end..]);
+    new.freeze()
+}
 
-    match responses.next().await? {
-        Message::BindComplete => {}
-        _ => return Err(Error::unexpected_message()),
+async fn bind_complete(responses: &mut Responses) -> Result<(), Error> {
+    loop {
+        match responses.next().await? {
+            Message::ParseComplete => {}
+            Message::BindComplete => return Ok(()),
+            _ => return Err(Error::unexpected_message()),
+        }
     }
+}
 
+async fn start(
+    client: &InnerClient,
+    buf: Bytes,
+    notices: Option<&NoticeSink>,
+) -> Result<Responses, Error> {
+    let mut responses = client
+        .send_with_notices(
+            RequestMessages::Single(FrontendMessage::Raw(buf)),
+            notices.cloned(),
+        )
+        .await?;
+    bind_complete(&mut responses).await?;
     Ok(responses)
 }
 
@@ -146,6 +260,7 @@ where
     I::IntoIter: ExactSizeIterator,
 {
     client.with_buf(|buf| {
+        encode_parse(statement, buf)?;
         encode_bind(statement, params, "", buf)?;
         frontend::execute("", 0, buf).map_err(Error::encode)?;
         frontend::sync(buf);
@@ -153,6 +268,20 @@ where
     })
 }
 
+/// Parses an unnamed statement's query again, as the unnamed statement may have been replaced since it was prepared.
+pub fn encode_parse(statement: &Statement, buf: &mut BytesMut) -> Result<(), Error> {
+    if statement.is_unnamed() {
+        frontend::parse(
+            "",
+            statement.query(),
+            statement.params().iter().map(Type::oid),
+            buf,
+        )
+        .map_err(Error::encode)?;
+    }
+    Ok(())
+}
+
 pub fn encode_bind<P, I>(
     statement: &Statement,
     params: I,
@@ -208,6 +337,8 @@ pin_project! {
         statement: Statement,
         responses: Responses,
         rows_affected: Option<u64>,
+        empty_query: bool,
+        timings: QueryTimings,
         #[pin]
         _p: PhantomPinned,
     }
@@ -221,12 +352,21 @@ impl Stream for RowStream {
         loop {
             match ready!(this.responses.poll_next(cx)?) {
                 Message::DataRow(body) => {
-                    return Poll::Ready(Some(Ok(Row::new(this.statement.clone(), body)?)))
+                    this.timings.first_row();
+                    return Poll::Ready(Some(Ok(Row::new(this.statement.clone(), body)?)));
                 }
                 Message::CommandComplete(body) => {
                     *this.rows_affected = Some(extract_row_affected(&body)?);
+                    this.timings.complete();
+                }
+                Message::EmptyQueryResponse => {
+                    *this.rows_affected = Some(0);
+                    *this.empty_query = true;
+                    this.timings.complete();
+                }
+                Message::PortalSuspended => {
+                    this.timings.complete();
                 }
-                Message::EmptyQueryResponse | Message::PortalSuspended => {}
                 Message::ReadyForQuery(_) => return Poll::Ready(None),
                 _ => return Poll::Ready(Some(Err(Error::unexpected_message()))),
             }
@@ -241,4 +381,125 @@ impl RowStream {
     pub fn rows_affected(&self) -> Option<u64> {
         self.rows_affected
     }
+
+    /// Determines if the query was empty, for example if it consisted only of whitespace or comments.
+    ///
+    /// Empty queries return no rows and affect none. This function will return `false` until the stream has been
+    /// exhausted.
+    pub fn is_empty_query(&self) -> bool {
+        self.empty_query
+    }
+
+    /// Returns timestamps recorded over the course of the query.
+    pub fn timings(&self) -> QueryTimings {
+        self.timings
+    }
+}
+
+/// Timestamps recorded by the client over the course of a query.
+///
+/// Comparing these can help separate time spent by the server executing the query from time spent transferring rows
+/// over the network. The time from `sent_at` to `first_row_at` covers the round trip and the server's planning and
+/// initial execution, while the time from `first_row_at` to `completed_at` is dominated by streaming the rows.
+/// Note that this reflects when messages are processed by the client, so a slow consumer of the rows will also
+/// inflate the later timestamps.
+///
+/// No clock is available on `wasm32` targets, so all timestamps are `None` there.
+#[derive(Debug, Copy, Clone)]
+pub struct QueryTimings {
+    sent_at: Option<Instant>,
+    first_row_at: Option<Instant>,
+    completed_at: Option<Instant>,
+}
+
+impl QueryTimings {
+    fn new() -> QueryTimings {
+        QueryTimings {
+            sent_at: now(),
+            first_row_at: None,
+            completed_at: None,
+        }
+    }
+
+    fn first_row(&mut self) {
+        if self.first_row_at.is_none() {
+            self.first_row_at = now();
+        }
+    }
+
+    fn complete(&mut self) {
+        if self.completed_at.is_none() {
+            self.completed_at = now();
+        }
+    }
+
+    /// Returns the time at which the query was sent to the server.
+    pub fn sent_at(&self) -> Option<Instant> {
+        self.sent_at
+    }
+
+    /// Returns the time at which the first row was received, if any have been.
+    pub fn first_row_at(&self) -> Option<Instant> {
+        self.first_row_at
+    }
+
+    /// Returns the time at which the server reported the query complete, if it has.
+    pub fn completed_at(&self) -> Option<Instant> {
+        self.completed_at
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn now() -> Option<Instant> {
+    Some(Instant::now())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn now() -> Option<Instant> {
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn db_error(fields: &[(u8, &str)]) -> Error {
+        let mut body = BytesMut::new();
+        for (tag, value) in fields {
+            body.put_u8(*tag);
+            body.put_slice(value.as_bytes());
+            body.put_u8(0);
+        }
+        body.put_u8(0);
+
+        let mut buf = BytesMut::new();
+        buf.put_u8(b'E');
+        buf.put_i32(body.len() as i32 + 4);
+        buf.put_slice(&body);
+        match Message::parse(&mut buf).unwrap().unwrap() {
+            Message::ErrorResponse(body) => Error::db(body),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn stale_translated() {
+        let error = db_error(&[
+            (b'S', "FEHLER"),
+            (b'V', "ERROR"),
+            (b'C', "0A000"),
+            (b'M', "gecachter Plan darf den Ergebnistyp nicht ändern"),
+            (b'R', "RevalidateCachedQuery"),
+        ]);
+        assert!(is_stale(&error));
+
+        let error = db_error(&[
+            (b'S', "ERROR"),
+            (b'V', "ERROR"),
+            (b'C', "0A000"),
+            (b'M', "cached plan must not change result type"),
+            (b'R', "transformSelectStmt"),
+        ]);
+        assert!(!is_stale(&error));
+    }
 }
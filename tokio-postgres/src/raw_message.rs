@@ -0,0 +1,104 @@
+use crate::client::{InnerClient, Responses};
+use crate::codec::FrontendMessage;
+use crate::connection::RequestMessages;
+use crate::Error;
+use byteorder::{BigEndian, ByteOrder};
+use bytes::Bytes;
+use futures_util::{ready, Stream};
+use log::debug;
+use pin_project_lite::pin_project;
+use std::io;
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+const READY_FOR_QUERY_TAG: u8 = b'Z';
+
+pub async fn send_raw(client: &InnerClient, messages: Bytes) -> Result<RawMessageStream, Error> {
+    validate(&messages).map_err(Error::encode)?;
+    debug!("sending {} bytes of raw frontend messages", messages.len());
+
+    let responses = client
+        .send_raw(RequestMessages::Single(FrontendMessage::Raw(messages)))
+        .await?;
+    Ok(RawMessageStream {
+        responses,
+        done: false,
+        _p: PhantomPinned,
+    })
+}
+
+/// Checks that the messages are well framed, and that only the last completes the request, so the server responds with
+/// exactly one `ReadyForQuery`.
+fn validate(mut buf: &[u8]) -> io::Result<()> {
+    if buf.is_empty() {
+        return Err(invalid("no messages to send"));
+    }
+
+    while !buf.is_empty() {
+        if buf.len() < 5 {
+            return Err(invalid("truncated message header"));
+        }
+        let tag = buf[0];
+        let len = BigEndian::read_i32(&buf[1..]);
+        if len < 4 || len as usize + 1 > buf.len() {
+            return Err(invalid("invalid message length"));
+        }
+        let next = &buf[len as usize + 1..];
+
+        match tag {
+            b'S' | b'Q' | b'F' if !next.is_empty() => {
+                return Err(invalid(
+                    "Sync, Query and FunctionCall messages may only be sent as the final message",
+                ))
+            }
+            b'S' | b'Q' | b'F' => {}
+            b'X' => return Err(invalid("Terminate messages may not be sent")),
+            b'd' | b'c' | b'f' => return Err(invalid("COPY messages may not be sent")),
+            _ if next.is_empty() => {
+                return Err(invalid(
+                    "the final message must be a Sync, Query or FunctionCall",
+                ))
+            }
+            _ => {}
+        }
+
+        buf = next;
+    }
+
+    Ok(())
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+
+pin_project! {
+    /// A stream of raw backend messages sent in response to `Client::send_raw`.
+    ///
+    /// Each item is a complete message, including its tag and length. The stream ends after the `ReadyForQuery`
+    /// message, which is included.
+    pub struct RawMessageStream {
+        responses: Responses,
+        done: bool,
+        #[pin]
+        _p: PhantomPinned,
+    }
+}
+
+impl Stream for RawMessageStream {
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        if *this.done {
+            return Poll::Ready(None);
+        }
+
+        let message = ready!(this.responses.poll_next_raw(cx)?);
+        if message[0] == READY_FOR_QUERY_TAG {
+            *this.done = true;
+        }
+        Poll::Ready(Some(Ok(message)))
+    }
+}
@@ -5,6 +5,7 @@ use crate::simple_query::SimpleColumn;
 use crate::statement::Column;
 use crate::types::{FromSql, Type, WrongType};
 use crate::{Error, Statement};
+use bytes::Bytes;
 use fallible_iterator::FallibleIterator;
 use postgres_protocol::message::backend::DataRowBody;
 use std::fmt;
@@ -183,6 +184,56 @@ impl Row {
         FromSql::from_sql_nullable(ty, self.col_buffer(idx)).map_err(|e| Error::from_sql(e, idx))
     }
 
+    /// Returns the value of a `BYTEA` column as `Bytes` sharing the row's buffer, or `None` if the value is `NULL`.
+    ///
+    /// Unlike deserializing into a `Vec<u8>`, this does not copy the data, and unlike `&[u8]` the result is not tied
+    /// to the lifetime of the row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds or if the column is not a `BYTEA`.
+    #[track_caller]
+    pub fn get_bytes<I>(&self, idx: I) -> Option<Bytes>
+    where
+        I: RowIndex + fmt::Display,
+    {
+        match self.get_bytes_inner(&idx) {
+            Ok(ok) => ok,
+            Err(err) => panic!("error retrieving column {}: {}", idx, err),
+        }
+    }
+
+    /// Like `Row::get_bytes`, but returns a `Result` rather than panicking.
+    pub fn try_get_bytes<I>(&self, idx: I) -> Result<Option<Bytes>, Error>
+    where
+        I: RowIndex + fmt::Display,
+    {
+        self.get_bytes_inner(&idx)
+    }
+
+    fn get_bytes_inner<I>(&self, idx: &I) -> Result<Option<Bytes>, Error>
+    where
+        I: RowIndex + fmt::Display,
+    {
+        let idx = match idx.__idx(self.columns()) {
+            Some(idx) => idx,
+            None => return Err(Error::column(idx.to_string())),
+        };
+
+        let ty = self.columns()[idx].type_();
+        if !<&[u8] as FromSql>::accepts(ty) {
+            return Err(Error::from_sql(
+                Box::new(WrongType::new::<Bytes>(ty.clone())),
+                idx,
+            ));
+        }
+
+        // the binary representation of BYTEA is the raw bytes themselves
+        Ok(self.ranges[idx]
+            .clone()
+            .map(|range| self.body.buffer_bytes().slice(range)))
+    }
+
     /// Get the raw bytes for the column at the given index.
     fn col_buffer(&self, idx: usize) -> Option<&[u8]> {
         let range = self.ranges[idx].to_owned()?;
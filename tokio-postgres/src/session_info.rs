@@ -0,0 +1,93 @@
+use crate::statement::{Column, Statement};
+use crate::types::Type;
+use crate::{query, slice_iter, Client, Error};
+use futures_util::{pin_mut, TryStreamExt};
+
+const SESSION_INFO_QUERY: &str = "SELECT current_user, current_database(), current_schema(), \
+                                  pg_backend_pid(), current_setting('server_version'), pg_is_in_recovery()";
+
+/// A snapshot of the state of a session, as reported by the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionInfo {
+    pub(crate) current_user: String,
+    pub(crate) current_database: String,
+    pub(crate) current_schema: Option<String>,
+    pub(crate) backend_pid: i32,
+    pub(crate) server_version: String,
+    pub(crate) in_recovery: bool,
+}
+
+impl SessionInfo {
+    /// Returns the name of the role the session is running as, the result of `current_user`.
+    pub fn current_user(&self) -> &str {
+        &self.current_user
+    }
+
+    /// Returns the name of the database the session is connected to.
+    pub fn current_database(&self) -> &str {
+        &self.current_database
+    }
+
+    /// Returns the first schema in the session's `search_path` which exists, if there is one.
+    pub fn current_schema(&self) -> Option<&str> {
+        self.current_schema.as_deref()
+    }
+
+    /// Returns the process ID of the server process handling the session.
+    pub fn backend_pid(&self) -> i32 {
+        self.backend_pid
+    }
+
+    /// Returns the server's version, the value of the `server_version` setting.
+    pub fn server_version(&self) -> &str {
+        &self.server_version
+    }
+
+    /// Returns whether the server is a standby which is still in recovery, rather than a primary.
+    pub fn in_recovery(&self) -> bool {
+        self.in_recovery
+    }
+}
+
+/// The result types of `SESSION_INFO_QUERY`, which are fixed, so the query can be parsed and executed in one request
+/// rather than first asking the server to describe it.
+const SESSION_INFO_COLUMNS: [(&str, Type); 6] = [
+    ("current_user", Type::NAME),
+    ("current_database", Type::NAME),
+    ("current_schema", Type::NAME),
+    ("pg_backend_pid", Type::INT4),
+    ("current_setting", Type::TEXT),
+    ("pg_is_in_recovery", Type::BOOL),
+];
+
+pub async fn session_info(client: &Client) -> Result<SessionInfo, Error> {
+    let columns = SESSION_INFO_COLUMNS
+        .iter()
+        .map(|(name, type_)| Column {
+            name: name.to_string(),
+            table_oid: None,
+            column_id: None,
+            type_modifier: None,
+            r#type: type_.clone(),
+        })
+        .collect();
+    let statement = Statement::unnamed(
+        client.inner(),
+        SESSION_INFO_QUERY.to_string(),
+        vec![],
+        columns,
+    );
+
+    let rows = query::query(client.inner(), statement, slice_iter(&[]), None).await?;
+    pin_mut!(rows);
+    let row = rows.try_next().await?.ok_or_else(Error::row_count)?;
+
+    Ok(SessionInfo {
+        current_user: row.try_get(0)?,
+        current_database: row.try_get(1)?,
+        current_schema: row.try_get(2)?,
+        backend_pid: row.try_get(3)?,
+        server_version: row.try_get(4)?,
+        in_recovery: row.try_get(5)?,
+    })
+}
@@ -36,7 +36,9 @@ pub async fn simple_query(client: &InnerClient, query: &str) -> Result<SimpleQue
     debug!("executing simple query: {}", query);
 
     let buf = encode(client, query)?;
-    let responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+    let responses = client
+        .send(RequestMessages::Single(FrontendMessage::Raw(buf)))
+        .await?;
 
     Ok(SimpleQueryStream {
         responses,
@@ -49,7 +51,9 @@ pub async fn batch_execute(client: &InnerClient, query: &str) -> Result<(), Erro
     debug!("executing statement batch: {}", query);
 
     let buf = encode(client, query)?;
-    let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+    let mut responses = client
+        .send(RequestMessages::Single(FrontendMessage::Raw(buf)))
+        .await?;
 
     loop {
         match responses.next().await? {
@@ -85,35 +89,31 @@ impl Stream for SimpleQueryStream {
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let this = self.project();
-        loop {
-            match ready!(this.responses.poll_next(cx)?) {
-                Message::CommandComplete(body) => {
-                    let rows = extract_row_affected(&body)?;
-                    return Poll::Ready(Some(Ok(SimpleQueryMessage::CommandComplete(rows))));
-                }
-                Message::EmptyQueryResponse => {
-                    return Poll::Ready(Some(Ok(SimpleQueryMessage::CommandComplete(0))));
-                }
-                Message::RowDescription(body) => {
-                    let columns = body
-                        .fields()
-                        .map(|f| Ok(SimpleColumn::new(f.name().to_string())))
-                        .collect::<Vec<_>>()
-                        .map_err(Error::parse)?
-                        .into();
+        let message = match ready!(this.responses.poll_next(cx)?) {
+            Message::CommandComplete(body) => {
+                SimpleQueryMessage::CommandComplete(extract_row_affected(&body)?)
+            }
+            Message::EmptyQueryResponse => SimpleQueryMessage::CommandComplete(0),
+            Message::RowDescription(body) => {
+                let columns: Arc<[SimpleColumn]> = body
+                    .fields()
+                    .map(|f| Ok(SimpleColumn::new(f.name().to_string())))
+                    .collect::<Vec<_>>()
+                    .map_err(Error::parse)?
+                    .into();
 
-                    *this.columns = Some(columns);
-                }
-                Message::DataRow(body) => {
-                    let row = match &this.columns {
-                        Some(columns) => SimpleQueryRow::new(columns.clone(), body)?,
-                        None => return Poll::Ready(Some(Err(Error::unexpected_message()))),
-                    };
-                    return Poll::Ready(Some(Ok(SimpleQueryMessage::Row(row))));
-                }
-                Message::ReadyForQuery(_) => return Poll::Ready(None),
-                _ => return Poll::Ready(Some(Err(Error::unexpected_message()))),
+                *this.columns = Some(columns.clone());
+                SimpleQueryMessage::RowDescription(columns)
             }
-        }
+            Message::DataRow(body) => match &this.columns {
+                Some(columns) => {
+                    SimpleQueryMessage::Row(SimpleQueryRow::new(columns.clone(), body)?)
+                }
+                None => return Poll::Ready(Some(Err(Error::unexpected_message()))),
+            },
+            Message::ReadyForQuery(_) => return Poll::Ready(None),
+            _ => return Poll::Ready(Some(Err(Error::unexpected_message()))),
+        };
+        Poll::Ready(Some(Ok(message)))
     }
 }
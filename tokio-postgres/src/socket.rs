@@ -1,3 +1,4 @@
+use socket2::SockRef;
 use std::io;
 use std::pin::Pin;
 use std::task::{Context, Poll};
@@ -28,6 +29,15 @@ impl Socket {
     pub(crate) fn new_unix(stream: UnixStream) -> Socket {
         Socket(Inner::Unix(stream))
     }
+
+    /// Returns a reference to the underlying socket if it is a TCP socket.
+    pub(crate) fn tcp_sock_ref(&self) -> Option<SockRef<'_>> {
+        match &self.0 {
+            Inner::Tcp(s) => Some(SockRef::from(s)),
+            #[cfg(unix)]
+            Inner::Unix(_) => None,
+        }
+    }
 }
 
 impl AsyncRead for Socket {
@@ -73,3 +83,14 @@ impl AsyncWrite for Socket {
         }
     }
 }
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn unix_has_no_tcp_sock_ref() {
+        let (socket, _peer) = UnixStream::pair().unwrap();
+        assert!(Socket::new_unix(socket).tcp_sock_ref().is_none());
+    }
+}
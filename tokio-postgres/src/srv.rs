@@ -0,0 +1,213 @@
+//! SRV service discovery.
+//!
+//! Records are looked up with `hickory-resolver`, configured from the system's resolver configuration.
+
+use hickory_resolver::error::ResolveErrorKind;
+use hickory_resolver::TokioAsyncResolver;
+use log::debug;
+use rand::Rng;
+use std::net::IpAddr;
+use std::time::Duration;
+use tokio::time;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A target discovered from an SRV record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SrvTarget {
+    pub priority: u16,
+    pub weight: u16,
+    pub port: u16,
+    pub target: String,
+}
+
+/// The result of a successful SRV lookup.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum SrvLookup {
+    /// The targets to try, in the order they should be tried.
+    Targets(Vec<SrvTarget>),
+    /// The domain has a single record with a target of `.`, meaning the service is decidedly not available there.
+    Unavailable,
+}
+
+/// Looks up the `_postgresql._tcp` SRV records for `host`.
+///
+/// Returns `None` if `host` is an IP address, no records exist, or the lookup fails, in which case the caller falls
+/// back to resolving the host itself. Targets are ordered by priority, and by the weighted random selection described
+/// in RFC 2782 within a priority.
+pub(crate) async fn lookup(host: &str, timeout: Option<Duration>) -> Option<SrvLookup> {
+    if host.parse::<IpAddr>().is_ok() {
+        return None;
+    }
+
+    let resolver = match TokioAsyncResolver::tokio_from_system_conf() {
+        Ok(resolver) => resolver,
+        Err(e) => {
+            debug!(
+                "not looking up SRV records: unable to read the system resolver configuration: {}",
+                e
+            );
+            return None;
+        }
+    };
+
+    // the resolver appends the search domains itself, following the `ndots` option
+    let name = format!("_postgresql._tcp.{}", host);
+    let timeout = timeout.unwrap_or(DEFAULT_TIMEOUT);
+    let records = match time::timeout(timeout, resolver.srv_lookup(name.as_str())).await {
+        Ok(Ok(records)) => records,
+        Ok(Err(e)) => {
+            match e.kind() {
+                ResolveErrorKind::NoRecordsFound { .. } => {
+                    debug!("no SRV records found for {}", name)
+                }
+                _ => debug!("SRV lookup of {} failed: {}", name, e),
+            }
+            return None;
+        }
+        Err(_) => {
+            debug!("SRV lookup of {} timed out", name);
+            return None;
+        }
+    };
+
+    let targets = records
+        .iter()
+        .map(|srv| SrvTarget {
+            priority: srv.priority(),
+            weight: srv.weight(),
+            port: srv.port(),
+            // a target of `.` is left empty
+            target: srv.target().to_utf8().trim_end_matches('.').to_string(),
+        })
+        .collect();
+    select(targets, &mut rand::thread_rng())
+}
+
+/// Interprets the targets of a domain's SRV records, ordering those which are available.
+fn select<R>(targets: Vec<SrvTarget>, rng: &mut R) -> Option<SrvLookup>
+where
+    R: Rng,
+{
+    if let [target] = &*targets {
+        if target.target.is_empty() {
+            return Some(SrvLookup::Unavailable);
+        }
+    }
+
+    let targets = targets
+        .into_iter()
+        .filter(|t| !t.target.is_empty())
+        .collect::<Vec<_>>();
+    if targets.is_empty() {
+        return None;
+    }
+    Some(SrvLookup::Targets(order(targets, rng)))
+}
+
+/// Orders targets as described in RFC 2782: by ascending priority, and then by repeatedly picking a random target
+/// from those that remain at a priority, with a probability proportional to its weight.
+fn order<R>(mut targets: Vec<SrvTarget>, rng: &mut R) -> Vec<SrvTarget>
+where
+    R: Rng,
+{
+    // zero weights go first, so they have a small chance of being selected when there are others
+    targets.sort_by_key(|t| (t.priority, t.weight != 0));
+
+    let mut ordered = Vec::with_capacity(targets.len());
+    while let Some(priority) = targets.first().map(|t| t.priority) {
+        let end = targets
+            .iter()
+            .position(|t| t.priority != priority)
+            .unwrap_or(targets.len());
+        let mut group = targets.drain(..end).collect::<Vec<_>>();
+        while !group.is_empty() {
+            let total = group.iter().map(|t| u32::from(t.weight)).sum::<u32>();
+            let pick = rng.gen_range(0..=total);
+            let mut sum = 0;
+            let idx = group
+                .iter()
+                .position(|t| {
+                    sum += u32::from(t.weight);
+                    sum >= pick
+                })
+                .unwrap_or(0);
+            ordered.push(group.remove(idx));
+        }
+    }
+    ordered
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn target(priority: u16, weight: u16, target: &str) -> SrvTarget {
+        SrvTarget {
+            priority,
+            weight,
+            port: 5432,
+            target: target.to_string(),
+        }
+    }
+
+    #[test]
+    fn order_by_priority() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let ordered = order(
+                vec![
+                    target(20, 1000, "c"),
+                    target(10, 0, "a"),
+                    target(30, 0, "d"),
+                    target(10, 0, "b"),
+                ],
+                &mut rng,
+            );
+            let priorities = ordered.iter().map(|t| t.priority).collect::<Vec<_>>();
+            assert_eq!(priorities, [10, 10, 20, 30]);
+        }
+    }
+
+    #[test]
+    fn order_by_weight() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut heavy_first = 0;
+        let mut zero_first = 0;
+        for _ in 0..1000 {
+            let ordered = order(
+                vec![
+                    target(10, 0, "zero"),
+                    target(10, 10, "light"),
+                    target(10, 90, "heavy"),
+                ],
+                &mut rng,
+            );
+            assert_eq!(ordered.len(), 3);
+            match &*ordered[0].target {
+                "heavy" => heavy_first += 1,
+                "zero" => zero_first += 1,
+                _ => {}
+            }
+        }
+        // the expected counts are roughly 891 and 10
+        assert!((820..960).contains(&heavy_first), "{}", heavy_first);
+        assert!(zero_first < 40, "{}", zero_first);
+    }
+
+    #[test]
+    fn unavailable() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(
+            select(vec![target(0, 0, "")], &mut rng),
+            Some(SrvLookup::Unavailable)
+        );
+        assert_eq!(select(vec![], &mut rng), None);
+        assert_eq!(
+            select(vec![target(0, 0, ""), target(10, 0, "db")], &mut rng),
+            Some(SrvLookup::Targets(vec![target(10, 0, "db")]))
+        );
+    }
+}
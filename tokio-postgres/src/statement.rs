@@ -3,24 +3,33 @@ use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
 use crate::types::Type;
 use postgres_protocol::message::frontend;
-use std::sync::{Arc, Weak};
+use std::sync::{Arc, OnceLock, Weak};
 
 struct StatementInner {
     client: Weak<InnerClient>,
     name: String,
+    query: String,
+    /// Whether this is the unnamed statement, whose query is parsed again each time it is executed.
+    unnamed: bool,
     params: Vec<Type>,
     columns: Vec<Column>,
+    /// The statement prepared again in this one's place after the server reported it missing or stale.
+    replacement: OnceLock<Statement>,
 }
 
 impl Drop for StatementInner {
     fn drop(&mut self) {
+        if self.unnamed {
+            return;
+        }
+
         if let Some(client) = self.client.upgrade() {
             let buf = client.with_buf(|buf| {
                 frontend::close(b'S', &self.name, buf).unwrap();
                 frontend::sync(buf);
                 buf.split().freeze()
             });
-            let _ = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)));
+            let _ = client.send_nowait(RequestMessages::Single(FrontendMessage::Raw(buf)));
         }
     }
 }
@@ -28,6 +37,14 @@ impl Drop for StatementInner {
 /// A prepared statement.
 ///
 /// Prepared statements can only be used with the connection that created them.
+///
+/// A statement stays valid until it is dropped, regardless of any transactions it is used in or was prepared in,
+/// unless the session discards it, for example with `DISCARD ALL`. If the server reports that the statement no longer
+/// exists when it is executed with one of the `query` or `execute` family of methods, it is prepared again and the
+/// call retried once, and the new statement is used in its place from then on, including by `params` and `columns`.
+/// This can't be done while a transaction is in progress, since the failure aborts the transaction, or while other
+/// requests are pipelined behind the failed one, since the retry would run after them. The error is returned in those
+/// cases.
 #[derive(Clone)]
 pub struct Statement(Arc<StatementInner>);
 
@@ -35,14 +52,35 @@ impl Statement {
     pub(crate) fn new(
         inner: &Arc<InnerClient>,
         name: String,
+        query: String,
         params: Vec<Type>,
         columns: Vec<Column>,
     ) -> Statement {
         Statement(Arc::new(StatementInner {
             client: Arc::downgrade(inner),
             name,
+            query,
+            unnamed: false,
+            params,
+            columns,
+            replacement: OnceLock::new(),
+        }))
+    }
+
+    pub(crate) fn unnamed(
+        inner: &Arc<InnerClient>,
+        query: String,
+        params: Vec<Type>,
+        columns: Vec<Column>,
+    ) -> Statement {
+        Statement(Arc::new(StatementInner {
+            client: Arc::downgrade(inner),
+            name: String::new(),
+            query,
+            unnamed: true,
             params,
             columns,
+            replacement: OnceLock::new(),
         }))
     }
 
@@ -50,14 +88,44 @@ impl Statement {
         &self.0.name
     }
 
+    pub(crate) fn query(&self) -> &str {
+        &self.0.query
+    }
+
+    /// Determines if this is the unnamed statement, which must be parsed before each use.
+    pub(crate) fn is_unnamed(&self) -> bool {
+        self.0.unnamed
+    }
+
+    /// Returns the statement used in place of this one, which is itself unless it has been prepared again.
+    fn latest(&self) -> &Statement {
+        let mut statement = self;
+        while let Some(replacement) = statement.0.replacement.get() {
+            statement = replacement;
+        }
+        statement
+    }
+
+    /// Returns the statement to execute in place of this one.
+    pub(crate) fn current(&self) -> Statement {
+        self.latest().clone()
+    }
+
+    /// Uses `statement` in place of this one from now on.
+    ///
+    /// If another caller replaced it first, that statement is kept.
+    pub(crate) fn set_replacement(&self, statement: Statement) {
+        let _ = self.latest().0.replacement.set(statement);
+    }
+
     /// Returns the expected types of the statement's parameters.
     pub fn params(&self) -> &[Type] {
-        &self.0.params
+        &self.latest().0.params
     }
 
     /// Returns information about the columns returned when the statement is queried.
     pub fn columns(&self) -> &[Column] {
-        &self.0.columns
+        &self.latest().0.columns
     }
 }
 
@@ -67,6 +135,7 @@ pub struct Column {
     pub(crate) name: String,
     pub(crate) table_oid: Option<u32>,
     pub(crate) column_id: Option<i16>,
+    pub(crate) type_modifier: Option<i32>,
     pub(crate) r#type: Type,
 }
 
@@ -86,6 +155,14 @@ impl Column {
         self.column_id
     }
 
+    /// Returns the type modifier of the column, if it has one.
+    ///
+    /// This is the raw `atttypmod` value, whose meaning depends on the type. For example, a `VARCHAR(n)` column has a
+    /// modifier of `n + 4`.
+    pub fn type_modifier(&self) -> Option<i32> {
+        self.type_modifier
+    }
+
     /// Returns the type of the column.
     pub fn type_(&self) -> &Type {
         &self.r#type
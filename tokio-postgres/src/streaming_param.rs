@@ -0,0 +1,223 @@
+use crate::client::InnerClient;
+use crate::codec::FrontendMessage;
+use crate::connection::RequestMessages;
+use crate::query::{self, extract_row_affected};
+use crate::types::{IsNull, ToSql};
+use crate::{Error, Statement};
+use byteorder::{BigEndian, ByteOrder};
+use bytes::{BufMut, Bytes, BytesMut};
+use futures_util::{ready, Stream};
+use log::debug;
+use parking_lot::Mutex;
+use postgres_protocol::message::backend::Message;
+use postgres_protocol::message::frontend;
+use std::convert::TryFrom;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::AsyncRead;
+use tokio_util::io::poll_read_buf;
+
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// A parameter value which is streamed to the server from an `AsyncRead` rather than buffered in memory.
+///
+/// Postgres needs the length of every parameter value up front, so the reader must produce exactly `len` bytes. They
+/// are sent as-is in the binary format of the parameter's type - for a `BYTEA` this is just the raw data.
+///
+/// This is not a `ToSql` type, since `ToSql` encodes values synchronously into the bind message buffer. It is instead
+/// passed to `Client::execute_streaming`, which works with any statement, such as an `UPDATE` setting a `BYTEA` column,
+/// not just those a `COPY` could replace.
+pub struct StreamingParam<R> {
+    reader: R,
+    len: u32,
+}
+
+impl<R> StreamingParam<R>
+where
+    R: AsyncRead + Send + 'static,
+{
+    /// Creates a new streaming parameter which will read `len` bytes from `reader`.
+    pub fn new(reader: R, len: u32) -> StreamingParam<R> {
+        StreamingParam { reader, len }
+    }
+}
+
+pub struct StreamingParamReceiver {
+    reader: Pin<Box<dyn AsyncRead + Send>>,
+    buf: BytesMut,
+    head: Option<Bytes>,
+    tail: Option<Bytes>,
+    remaining: usize,
+    error: Arc<Mutex<Option<io::Error>>>,
+}
+
+impl Stream for StreamingParamReceiver {
+    type Item = FrontendMessage;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<FrontendMessage>> {
+        let this = &mut *self;
+
+        if let Some(head) = this.head.take() {
+            return Poll::Ready(Some(FrontendMessage::Raw(head)));
+        }
+
+        if this.remaining > 0 {
+            let len = usize::min(this.remaining, CHUNK_SIZE);
+            // the previous chunk has usually been written out by now, so this reclaims its allocation
+            this.buf.reserve(len);
+            let mut error = this.error.lock();
+            if error.is_none() {
+                match ready!(poll_read_buf(
+                    this.reader.as_mut(),
+                    cx,
+                    &mut (&mut this.buf).limit(len)
+                )) {
+                    Ok(0) => {
+                        *error = Some(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            "streaming parameter ended early",
+                        ));
+                    }
+                    Ok(_) => {}
+                    Err(e) => *error = Some(e),
+                }
+            }
+            // once the reader has failed, the rest of the value is padded with zeros so the server still sees a well
+            // formed message
+            if error.is_some() {
+                this.buf.resize(len, 0);
+            }
+            let chunk = this.buf.split().freeze();
+            this.remaining -= chunk.len();
+            return Poll::Ready(Some(FrontendMessage::Raw(chunk)));
+        }
+
+        match this.tail.take() {
+            Some(tail) if this.error.lock().is_some() => {
+                // the bound value is garbage, so finish off the bind message but skip the execute, syncing to discard
+                // the portal
+                let mut buf = BytesMut::from(&tail[..4]);
+                frontend::sync(&mut buf);
+                Poll::Ready(Some(FrontendMessage::Raw(buf.freeze())))
+            }
+            Some(tail) => Poll::Ready(Some(FrontendMessage::Raw(tail))),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+pub async fn execute<R>(
+    client: &InnerClient,
+    statement: Statement,
+    params: &[&(dyn ToSql + Sync)],
+    value: StreamingParam<R>,
+) -> Result<u64, Error>
+where
+    R: AsyncRead + Send + 'static,
+{
+    let statement = statement.current();
+    debug!(
+        "executing statement {} with a streamed parameter of {} bytes",
+        statement.name(),
+        value.len
+    );
+
+    let param_types = statement.params();
+    if param_types.len() != params.len() + 1 {
+        return Err(Error::parameters(params.len() + 1, param_types.len()));
+    }
+    let idx = params.len();
+
+    let mut formats = Vec::with_capacity(param_types.len());
+    let mut values = BytesMut::new();
+    for (i, (param, ty)) in params.iter().zip(param_types).enumerate() {
+        formats.push(param.encode_format(ty) as i16);
+        let base = values.len();
+        values.put_i32(0);
+        let len = match param.to_sql_checked(ty, &mut values) {
+            Ok(IsNull::No) => i32::try_from(values.len() - base - 4).map_err(|_| too_large())?,
+            Ok(IsNull::Yes) => -1,
+            Err(e) => return Err(Error::to_sql(e, i)),
+        };
+        BigEndian::write_i32(&mut values[base..], len);
+    }
+    // the streamed value is sent as-is, so it is always in the binary format
+    formats.push(1);
+    let count = i16::try_from(formats.len()).map_err(|_| too_large())?;
+
+    // The Bind message is written out by hand, since its length has to include the streamed value which follows the
+    // head. The tail holds the result format codes which end the Bind message, requesting binary results as
+    // `query::encode_bind` does.
+    let message_len = 4
+        + 1
+        + statement.name().len() as i64
+        + 1
+        + 2
+        + 2 * formats.len() as i64
+        + 2
+        + values.len() as i64
+        + 4
+        + value.len as i64
+        + 4;
+    let message_len = i32::try_from(message_len).map_err(|_| too_large())?;
+
+    let (head, tail) = client.with_buf(|buf| {
+        query::encode_parse(&statement, buf)?;
+        buf.put_u8(b'B');
+        buf.put_i32(message_len);
+        buf.put_u8(0);
+        buf.put_slice(statement.name().as_bytes());
+        buf.put_u8(0);
+        buf.put_i16(count);
+        for format in &formats {
+            buf.put_i16(*format);
+        }
+        buf.put_i16(count);
+        buf.put_slice(&values);
+        buf.put_i32(value.len as i32);
+        let head = buf.split().freeze();
+
+        buf.put_i16(1);
+        buf.put_i16(1);
+        frontend::execute("", 0, buf).map_err(Error::encode)?;
+        frontend::sync(buf);
+        Ok::<_, Error>((head, buf.split().freeze()))
+    })?;
+
+    let error = Arc::new(Mutex::new(None));
+    let receiver = StreamingParamReceiver {
+        reader: Box::pin(value.reader),
+        buf: BytesMut::new(),
+        head: Some(head),
+        tail: Some(tail),
+        remaining: value.len as usize,
+        error: error.clone(),
+    };
+    let mut responses = client
+        .send(RequestMessages::StreamingParam(receiver))
+        .await?;
+
+    let mut rows = 0;
+    loop {
+        let message = responses.next().await;
+        if let Some(e) = error.lock().take() {
+            return Err(Error::to_sql(Box::new(e), idx));
+        }
+        match message? {
+            Message::ParseComplete | Message::BindComplete | Message::DataRow(_) => {}
+            Message::CommandComplete(body) => rows = extract_row_affected(&body)?,
+            Message::EmptyQueryResponse => rows = 0,
+            Message::ReadyForQuery(_) => return Ok(rows),
+            _ => return Err(Error::unexpected_message()),
+        }
+    }
+}
+
+fn too_large() -> Error {
+    Error::encode(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "value too large to transmit",
+    ))
+}
@@ -1,5 +1,7 @@
 //! TLS support.
 
+#[cfg(feature = "runtime")]
+use crate::config::{SslMode, SslNegotiation};
 use std::error::Error;
 use std::future::Future;
 use std::pin::Pin;
@@ -25,6 +27,11 @@ impl ChannelBinding {
     }
 
     /// Creates a `ChannelBinding` containing `tls-server-end-point` channel binding information.
+    ///
+    /// The value is the hash of the server's DER-encoded certificate, as defined in [RFC 5929]. The hash function is
+    /// the one used in the certificate's signature algorithm, except that MD5 and SHA-1 are replaced by SHA-256.
+    ///
+    /// [RFC 5929]: https://datatracker.ietf.org/doc/html/rfc5929#section-4.1
     pub fn tls_server_end_point(tls_server_end_point: Vec<u8>) -> ChannelBinding {
         ChannelBinding {
             tls_server_end_point: Some(tls_server_end_point),
@@ -48,6 +55,35 @@ pub trait MakeTlsConnect<S> {
     ///
     /// The domain name is provided for certificate verification and SNI.
     fn make_tls_connect(&mut self, domain: &str) -> Result<Self::TlsConnect, Self::Error>;
+
+    /// Creates a new `TlsConnect`or for a connection using the given `sslmode`.
+    ///
+    /// This lets implementations tell `verify-ca`, which only verifies the server's certificate chain, apart from
+    /// `verify-full`, which also checks that the certificate matches the domain name. The default implementation
+    /// ignores the mode and calls `make_tls_connect`.
+    fn make_tls_connect_with_mode(
+        &mut self,
+        domain: &str,
+        mode: SslMode,
+    ) -> Result<Self::TlsConnect, Self::Error> {
+        let _ = mode;
+        self.make_tls_connect(domain)
+    }
+
+    /// Creates a new `TlsConnect`or for a connection using the given `sslmode` and `sslnegotiation`.
+    ///
+    /// This is what the crate calls, so implementations can offer the `postgresql` ALPN protocol, which the server
+    /// requires for `SslNegotiation::Direct`, only when it is needed. The default implementation ignores the
+    /// negotiation and calls `make_tls_connect_with_mode`.
+    fn make_tls_connect_with_negotiation(
+        &mut self,
+        domain: &str,
+        mode: SslMode,
+        negotiation: SslNegotiation,
+    ) -> Result<Self::TlsConnect, Self::Error> {
+        let _ = negotiation;
+        self.make_tls_connect_with_mode(domain, mode)
+    }
 }
 
 /// An asynchronous function wrapping a stream in a TLS session.
@@ -71,6 +107,9 @@ pub trait TlsConnect<S> {
 /// A TLS-wrapped connection to a PostgreSQL database.
 pub trait TlsStream: AsyncRead + AsyncWrite {
     /// Returns channel binding information for the session.
+    ///
+    /// When this contains `tls-server-end-point` data, the `SCRAM-SHA-256-PLUS` SASL mechanism is used if the server
+    /// offers it. Implementations which can't compute it should return `ChannelBinding::none()`.
     fn channel_binding(&self) -> ChannelBinding;
 }
 
@@ -1,6 +1,8 @@
 use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
+use crate::copy_builder::{CopyInBuilder, CopyOutBuilder};
 use crate::copy_out::CopyOutStream;
+use crate::error::DbError;
 use crate::query::RowStream;
 #[cfg(feature = "runtime")]
 use crate::tls::MakeTlsConnect;
@@ -9,8 +11,8 @@ use crate::types::{BorrowToSql, ToSql, Type};
 #[cfg(feature = "runtime")]
 use crate::Socket;
 use crate::{
-    bind, query, slice_iter, CancelToken, Client, CopyInSink, Error, Portal, Row,
-    SimpleQueryMessage, Statement, ToStatement,
+    bind, query, slice_iter, CancelToken, Client, CopyInSink, Error, Portal, Row, SessionInfo,
+    SimpleQueryMessage, Statement, StreamingParam, ToStatement,
 };
 use bytes::Buf;
 use futures_util::TryStreamExt;
@@ -51,7 +53,7 @@ impl Drop for Transaction<'_> {
         let _ = self
             .client
             .inner()
-            .send(RequestMessages::Single(FrontendMessage::Raw(buf)));
+            .send_nowait(RequestMessages::Single(FrontendMessage::Raw(buf)));
     }
 }
 
@@ -102,6 +104,11 @@ impl<'a> Transaction<'a> {
         self.client.prepare_typed(query, parameter_types).await
     }
 
+    /// Like `Client::prepare_batch`.
+    pub async fn prepare_batch(&self, queries: &[&str]) -> Result<Vec<Statement>, Error> {
+        self.client.prepare_batch(queries).await
+    }
+
     /// Like `Client::query`.
     pub async fn query<T>(
         &self,
@@ -149,6 +156,18 @@ impl<'a> Transaction<'a> {
         self.client.query_raw(statement, params).await
     }
 
+    /// Like `Client::query_with_notices`.
+    pub async fn query_with_notices<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Row>, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.client.query_with_notices(statement, params).await
+    }
+
     /// Like `Client::execute`.
     pub async fn execute<T>(
         &self,
@@ -172,6 +191,34 @@ impl<'a> Transaction<'a> {
         self.client.execute_raw(statement, params).await
     }
 
+    /// Like `Client::execute_with_notices`.
+    pub async fn execute_with_notices<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(u64, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.client.execute_with_notices(statement, params).await
+    }
+
+    /// Like `Client::execute_streaming`.
+    pub async fn execute_streaming<T, R>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+        value: StreamingParam<R>,
+    ) -> Result<u64, Error>
+    where
+        T: ?Sized + ToStatement,
+        R: AsyncRead + Send + 'static,
+    {
+        self.client
+            .execute_streaming(statement, params, value)
+            .await
+    }
+
     /// Binds a statement to a set of parameters, creating a `Portal` which can be incrementally queried.
     ///
     /// Portals only last for the duration of the transaction in which they are created, and can only be used on the
@@ -244,6 +291,16 @@ impl<'a> Transaction<'a> {
         self.client.copy_out(statement).await
     }
 
+    /// Like `Client::build_copy_in`.
+    pub fn build_copy_in(&self, table: &str) -> CopyInBuilder<'_> {
+        self.client.build_copy_in(table)
+    }
+
+    /// Like `Client::build_copy_out`.
+    pub fn build_copy_out(&self, table: &str) -> CopyOutBuilder<'_> {
+        self.client.build_copy_out(table)
+    }
+
     /// Like `Client::simple_query`.
     pub async fn simple_query(&self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error> {
         self.client.simple_query(query).await
@@ -254,6 +311,11 @@ impl<'a> Transaction<'a> {
         self.client.batch_execute(query).await
     }
 
+    /// Like `Client::session_info`.
+    pub async fn session_info(&self) -> Result<SessionInfo, Error> {
+        self.client.session_info().await
+    }
+
     /// Like `Client::cancel_token`.
     pub fn cancel_token(&self) -> CancelToken {
         self.client.cancel_token()
@@ -1,7 +1,95 @@
 use crate::connect;
-use futures_util::{pin_mut, TryStreamExt};
+use bytes::{BufMut, BytesMut};
+use futures_util::{pin_mut, ready, FutureExt, TryStreamExt};
+use std::convert::TryInto;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
 use tokio_postgres::binary_copy::{BinaryCopyInWriter, BinaryCopyOutStream};
 use tokio_postgres::types::Type;
+use tokio_postgres::{Client, Config, NoTls};
+
+/// A stream which splits every `CopyData` message from the server into many tiny ones.
+struct FragmentingStream {
+    inner: TcpStream,
+    raw: BytesMut,
+    out: BytesMut,
+}
+
+impl AsyncRead for FragmentingStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+
+        while this.out.is_empty() {
+            let mut read = [0; 4096];
+            let mut read = ReadBuf::new(&mut read);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut read))?;
+            if read.filled().is_empty() {
+                return Poll::Ready(Ok(()));
+            }
+            this.raw.put_slice(read.filled());
+
+            while this.raw.len() >= 5 {
+                let len = i32::from_be_bytes(this.raw[1..5].try_into().unwrap()) as usize;
+                if this.raw.len() < len + 1 {
+                    break;
+                }
+
+                let message = this.raw.split_to(len + 1);
+                if message[0] == b'd' {
+                    for part in message[5..].chunks(3) {
+                        this.out.put_u8(b'd');
+                        this.out.put_i32(part.len() as i32 + 4);
+                        this.out.put_slice(part);
+                    }
+                } else {
+                    this.out.put_slice(&message);
+                }
+            }
+        }
+
+        let len = usize::min(buf.remaining(), this.out.len());
+        buf.put_slice(&this.out.split_to(len));
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for FragmentingStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+async fn connect_fragmenting(s: &str) -> Client {
+    let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let stream = FragmentingStream {
+        inner: socket,
+        raw: BytesMut::new(),
+        out: BytesMut::new(),
+    };
+    let config = s.parse::<Config>().unwrap();
+    let (client, connection) = config.connect_raw(stream, NoTls).await.unwrap();
+    tokio::spawn(connection.map(|r| r.unwrap()));
+    client
+}
 
 #[tokio::test]
 async fn write_basic() {
@@ -37,6 +125,39 @@ async fn write_basic() {
     assert_eq!(rows[1].get::<_, Option<&str>>(1), None);
 }
 
+#[tokio::test]
+async fn builder_round_trip() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT, bar TEXT, baz TEXT)")
+        .await
+        .unwrap();
+
+    let writer = client
+        .build_copy_in("foo")
+        .columns(&["bar", "id"])
+        .start_binary(&[Type::TEXT, Type::INT4])
+        .await
+        .unwrap();
+    pin_mut!(writer);
+    writer.as_mut().write(&[&"foobar", &1i32]).await.unwrap();
+    writer.finish().await.unwrap();
+
+    let rows = client
+        .build_copy_out("foo")
+        .columns(&["id", "bar"])
+        .start_binary(&[Type::INT4, Type::TEXT])
+        .await
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<i32>(0), 1);
+    assert_eq!(rows[0].get::<&str>(1), "foobar");
+}
+
 #[tokio::test]
 async fn write_many_rows() {
     let client = connect("user=postgres").await;
@@ -201,3 +322,40 @@ async fn read_big_rows() {
         assert_eq!(row.get::<&[u8]>(1), &vec![i as u8; 128 * 1024][..]);
     }
 }
+
+#[tokio::test]
+async fn read_fragmented() {
+    let client = connect_fragmenting("user=postgres").await;
+
+    client
+        .batch_execute(
+            "
+            CREATE TEMPORARY TABLE foo (id INT, bar TEXT, baz BYTEA);
+            INSERT INTO foo (id, bar, baz)
+                SELECT i, 'the value for ' || i, decode(repeat('ab', i), 'hex')
+                FROM generate_series(0, 99) i;
+            INSERT INTO foo (id, bar, baz) VALUES (100, NULL, NULL);
+            ",
+        )
+        .await
+        .unwrap();
+
+    let stream = client
+        .copy_out("COPY foo (id, bar, baz) TO STDIN BINARY")
+        .await
+        .unwrap();
+    let rows = BinaryCopyOutStream::new(stream, &[Type::INT4, Type::TEXT, Type::BYTEA])
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 101);
+
+    for (i, row) in rows[..100].iter().enumerate() {
+        assert_eq!(row.get::<i32>(0), i as i32);
+        assert_eq!(row.get::<&str>(1), format!("the value for {}", i));
+        assert_eq!(row.get::<&[u8]>(2), &vec![0xab; i][..]);
+    }
+    assert_eq!(rows[100].get::<i32>(0), 100);
+    assert_eq!(rows[100].get::<Option<&str>>(1), None);
+    assert_eq!(rows[100].get::<Option<&[u8]>>(2), None);
+}
@@ -0,0 +1,188 @@
+//! A scripted stand-in for a server, for responses a real one can't be made to send.
+
+use bytes::BytesMut;
+use futures_channel::oneshot;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// A step of a fake server's script.
+pub enum Step {
+    /// Reads a message without a tag, like the startup message or an `SSLRequest`.
+    ReadUntagged,
+    /// Reads a message, checking its tag.
+    Read(u8),
+    /// Writes the encoded messages.
+    Write(BytesMut),
+    /// Waits until the sender is used or dropped.
+    Wait(oneshot::Receiver<()>),
+}
+
+/// Accepts the connection without authentication.
+pub fn accept() -> Step {
+    messages(|buf| {
+        backend::authentication_ok(buf);
+        backend::backend_key_data(1, 2, buf);
+        backend::ready_for_query(b'I', buf);
+        Ok(())
+    })
+}
+
+/// Encodes messages with the functions of the `backend` module.
+pub fn messages(f: impl FnOnce(&mut BytesMut) -> std::io::Result<()>) -> Step {
+    let mut buf = BytesMut::new();
+    f(&mut buf).unwrap();
+    Step::Write(buf)
+}
+
+/// Runs the script, then waits for the client to hang up.
+///
+/// Returns the bodies of the messages read.
+pub async fn run<S, I>(mut stream: S, script: I) -> Vec<Vec<u8>>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    I: IntoIterator<Item = Step>,
+{
+    let mut read = vec![];
+    for step in script {
+        match step {
+            Step::ReadUntagged => read.push(read_body(&mut stream).await),
+            Step::Read(tag) => {
+                assert_eq!(stream.read_u8().await.unwrap(), tag);
+                read.push(read_body(&mut stream).await);
+            }
+            Step::Write(buf) => stream.write_all(&buf).await.unwrap(),
+            Step::Wait(receiver) => {
+                let _ = receiver.await;
+            }
+        }
+    }
+
+    let _ = stream.read_to_end(&mut vec![]).await;
+    read
+}
+
+async fn read_body<S>(stream: &mut S) -> Vec<u8>
+where
+    S: AsyncRead + Unpin,
+{
+    let len = stream.read_u32().await.unwrap();
+    let mut body = vec![0; len as usize - 4];
+    stream.read_exact(&mut body).await.unwrap();
+    body
+}
+
+/// Encoders of the messages a server sends during startup and simple queries.
+pub mod backend {
+    use bytes::{BufMut, BytesMut};
+    use std::io;
+
+    fn message<F>(tag: u8, buf: &mut BytesMut, f: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut BytesMut) -> io::Result<()>,
+    {
+        let mut body = BytesMut::new();
+        f(&mut body)?;
+
+        buf.put_u8(tag);
+        buf.put_i32(body.len() as i32 + 4);
+        buf.put_slice(&body);
+        Ok(())
+    }
+
+    fn cstr(s: &[u8], buf: &mut BytesMut) -> io::Result<()> {
+        if s.contains(&0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "string contains embedded null",
+            ));
+        }
+        buf.put_slice(s);
+        buf.put_u8(0);
+        Ok(())
+    }
+
+    pub fn authentication_ok(buf: &mut BytesMut) {
+        message(b'R', buf, |body| {
+            body.put_i32(0);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    pub fn authentication_sasl<'a, I>(mechanisms: I, buf: &mut BytesMut) -> io::Result<()>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        message(b'R', buf, |body| {
+            body.put_i32(10);
+            for mechanism in mechanisms {
+                cstr(mechanism.as_bytes(), body)?;
+            }
+            body.put_u8(0);
+            Ok(())
+        })
+    }
+
+    pub fn backend_key_data(process_id: i32, secret_key: i32, buf: &mut BytesMut) {
+        message(b'K', buf, |body| {
+            body.put_i32(process_id);
+            body.put_i32(secret_key);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    pub fn command_complete(tag: &str, buf: &mut BytesMut) -> io::Result<()> {
+        message(b'C', buf, |body| cstr(tag.as_bytes(), body))
+    }
+
+    /// Writes an `ErrorResponse` with the given fields, whose values aren't required to be UTF-8.
+    pub fn error_response<I, V>(fields: I, buf: &mut BytesMut) -> io::Result<()>
+    where
+        I: IntoIterator<Item = (u8, V)>,
+        V: AsRef<[u8]>,
+    {
+        message(b'E', buf, |body| {
+            for (tag, value) in fields {
+                body.put_u8(tag);
+                cstr(value.as_ref(), body)?;
+            }
+            body.put_u8(0);
+            Ok(())
+        })
+    }
+
+    pub fn negotiate_protocol_version<'a, I>(
+        newest_minor_version: i32,
+        options: I,
+        buf: &mut BytesMut,
+    ) -> io::Result<()>
+    where
+        I: IntoIterator<Item = &'a str>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        message(b'v', buf, |body| {
+            let options = options.into_iter();
+            body.put_i32(newest_minor_version);
+            body.put_i32(options.len() as i32);
+            for option in options {
+                cstr(option.as_bytes(), body)?;
+            }
+            Ok(())
+        })
+    }
+
+    pub fn parameter_status(name: &str, value: &str, buf: &mut BytesMut) -> io::Result<()> {
+        message(b'S', buf, |body| {
+            cstr(name.as_bytes(), body)?;
+            cstr(value.as_bytes(), body)
+        })
+    }
+
+    pub fn ready_for_query(status: u8, buf: &mut BytesMut) {
+        message(b'Z', buf, |body| {
+            body.put_u8(status);
+            Ok(())
+        })
+        .unwrap();
+    }
+}
@@ -1,25 +1,33 @@
 #![warn(rust_2018_idioms)]
 
 use bytes::{Bytes, BytesMut};
+use fake_server::{backend, Step};
 use futures_channel::mpsc;
 use futures_util::{
     future, join, pin_mut, stream, try_join, Future, FutureExt, SinkExt, StreamExt, TryStreamExt,
 };
 use pin_project_lite::pin_project;
+use postgres_protocol::message::frontend;
 use std::fmt::Write;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, ReadBuf};
 use tokio::net::TcpStream;
 use tokio::time;
+use tokio_postgres::config::{SslMode, SslNegotiation};
 use tokio_postgres::error::SqlState;
-use tokio_postgres::tls::{NoTls, NoTlsStream};
+use tokio_postgres::tls::{NoTls, NoTlsStream, TlsConnect};
 use tokio_postgres::types::{Kind, Type};
 use tokio_postgres::{
-    AsyncMessage, Client, Config, Connection, Error, IsolationLevel, SimpleQueryMessage,
+    AsyncMessage, AuthMethod, Client, Config, Connection, CopyFormat, Error, GenericClient,
+    IsolationLevel, SimpleQueryMessage, StreamingParam, Transport,
 };
 
 mod binary_copy;
+mod fake_server;
 mod parse;
 #[cfg(feature = "runtime")]
 mod replication;
@@ -149,20 +157,951 @@ async fn scram_password_ok() {
     connect("user=scram_user password=password dbname=postgres").await;
 }
 
+#[tokio::test]
+async fn scram_min_iterations() {
+    let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let mut config = "user=scram_user password=password dbname=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.scram_min_iterations(1_000_000);
+    match config.connect_raw(socket, NoTls).await {
+        Ok(_) => panic!("unexpected success"),
+        Err(e) => assert!(e.to_string().contains("iteration count"), "{}", e),
+    }
+}
+
+#[tokio::test]
+async fn scram_plus_without_tls() {
+    // a server offering channel binding over a stream which was already wrapped in TLS before being passed in
+    let offer = || {
+        fake_server::messages(|buf| {
+            backend::authentication_sasl(["SCRAM-SHA-256-PLUS", "SCRAM-SHA-256"], buf)
+        })
+    };
+
+    let (client_stream, server_stream) = tokio::io::duplex(4096);
+    tokio::spawn(fake_server::run(
+        server_stream,
+        [Step::ReadUntagged, offer()],
+    ));
+    let config = "user=postgres password=password channel_binding=require"
+        .parse::<Config>()
+        .unwrap();
+    let err = config
+        .connect_raw(client_stream, NoTls)
+        .await
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("non-TLS connection"), "{}", err);
+
+    // without channel binding required, the client falls back to plain SCRAM-SHA-256
+    let (client_stream, server_stream) = tokio::io::duplex(4096);
+    let server = tokio::spawn(fake_server::run(
+        server_stream,
+        [
+            Step::ReadUntagged,
+            offer(),
+            Step::Read(b'p'),
+            fake_server::messages(|buf| {
+                backend::error_response(
+                    [
+                        (b'S', "FATAL"),
+                        (b'C', "28P01"),
+                        (b'M', "password authentication failed"),
+                    ],
+                    buf,
+                )
+            }),
+        ],
+    ));
+    let config = "user=postgres password=password channel_binding=prefer"
+        .parse::<Config>()
+        .unwrap();
+    let err = config
+        .connect_raw(client_stream, NoTls)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(err.code(), Some(&SqlState::INVALID_PASSWORD));
+    let read = server.await.unwrap();
+    assert!(read[1].starts_with(b"SCRAM-SHA-256\0"), "{:?}", read[1]);
+    assert!(read[1][18..].starts_with(b"n,,"), "{:?}", read[1]);
+}
+
+#[tokio::test]
+async fn connection_info() {
+    for (s, auth_method) in [
+        ("user=postgres", AuthMethod::Trust),
+        (
+            "user=pass_user password=password dbname=postgres",
+            AuthMethod::Password,
+        ),
+        (
+            "user=md5_user password=password dbname=postgres",
+            AuthMethod::Md5,
+        ),
+        (
+            "user=scram_user password=password dbname=postgres",
+            AuthMethod::ScramSha256,
+        ),
+    ] {
+        let info = connect(s).await.connection_info();
+        assert_eq!(info.auth_method(), auth_method, "`{}`", s);
+        assert_eq!(info.transport(), Transport::Plaintext, "`{}`", s);
+    }
+}
+
+#[tokio::test]
+async fn session_info() {
+    let client = connect("user=postgres dbname=postgres").await;
+    client
+        .batch_execute("SET search_path = pg_catalog")
+        .await
+        .unwrap();
+
+    let info = client.session_info().await.unwrap();
+    assert_eq!(info.current_user(), "postgres");
+    assert_eq!(info.current_database(), "postgres");
+    assert_eq!(info.current_schema(), Some("pg_catalog"));
+    assert!(!info.in_recovery());
+
+    let row = client
+        .query_one(
+            "SELECT pg_backend_pid(), current_setting('server_version')",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(info.backend_pid(), row.get::<_, i32>(0));
+    assert_eq!(info.server_version(), row.get::<_, &str>(1));
+
+    client.batch_execute("SET search_path = ''").await.unwrap();
+    let info = client.session_info().await.unwrap();
+    assert_eq!(info.current_schema(), None);
+}
+
+#[tokio::test]
+async fn options_escaping() {
+    let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config
+        .option("search_path", "\"my schema\", public")
+        .option("application_name", "back\\slash");
+    let (client, connection) = config.connect_raw(socket, NoTls).await.unwrap();
+    tokio::spawn(connection.map(|r| r.unwrap()));
+
+    let row = client
+        .query_one(
+            "SELECT current_setting('search_path'), current_setting('application_name')",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, &str>(0), "\"my schema\", public");
+    assert_eq!(row.get::<_, &str>(1), "back\\slash");
+}
+
+#[tokio::test]
+async fn fallback_application_name() {
+    for (s, expected) in [
+        (
+            "user=postgres fallback_application_name=fallback",
+            "fallback",
+        ),
+        (
+            "user=postgres application_name=app fallback_application_name=fallback",
+            "app",
+        ),
+    ] {
+        let client = connect(s).await;
+        let row = client
+            .query_one("SHOW application_name", &[])
+            .await
+            .unwrap();
+        assert_eq!(row.get::<_, &str>(0), expected, "`{}`", s);
+    }
+}
+
+#[tokio::test]
+async fn default_transaction_read_only() {
+    let mut client = connect("user=postgres default_transaction_read_only=on").await;
+
+    let row = client
+        .query_one("SHOW transaction_read_only", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, &str>(0), "on");
+
+    let err = client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT)")
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::READ_ONLY_SQL_TRANSACTION));
+
+    let transaction = client
+        .build_transaction()
+        .read_only(false)
+        .start()
+        .await
+        .unwrap();
+    transaction
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT)")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn max_backend_message_size() {
+    let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config.max_backend_message_size(1024);
+    let (client, connection) = config.connect_raw(socket, NoTls).await.unwrap();
+    let connection = tokio::spawn(connection);
+
+    let row = client.query_one("SELECT 'small'", &[]).await.unwrap();
+    assert_eq!(row.get::<_, &str>(0), "small");
+
+    let err = client
+        .query_one("SELECT repeat('a', 10000)", &[])
+        .await
+        .unwrap_err();
+    assert!(err.is_closed());
+
+    let err = connection.await.unwrap().unwrap_err();
+    assert!(
+        err.to_string().contains("exceeds the maximum size"),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn buffer_sizes() {
+    let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config.read_buffer_size(16).write_buffer_size(16);
+    let (client, connection) = config.connect_raw(socket, NoTls).await.unwrap();
+    tokio::spawn(connection);
+
+    let rows = client
+        .query("SELECT repeat('a', 1000) FROM generate_series(1, 10)", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 10);
+    assert_eq!(rows[9].get::<_, &str>(0).len(), 1000);
+}
+
+#[tokio::test]
+async fn row_buffer_limit() {
+    let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config.row_buffer_limit(3);
+    let (client, connection) = config.connect_raw(socket, NoTls).await.unwrap();
+    tokio::spawn(connection);
+
+    let rows = client
+        .query_raw(
+            "SELECT i FROM generate_series(1, 1000) i",
+            std::iter::empty::<i32>(),
+        )
+        .await
+        .unwrap();
+    pin_mut!(rows);
+    let mut expected = 1;
+    while let Some(row) = rows.try_next().await.unwrap() {
+        assert_eq!(row.get::<_, i32>(0), expected);
+        expected += 1;
+        if expected % 100 == 0 {
+            time::sleep(Duration::from_millis(1)).await;
+        }
+    }
+    assert_eq!(expected, 1001);
+    assert_eq!(rows.rows_affected(), Some(1000));
+
+    // the next request is still answered once the large result has been consumed
+    let row = client.query_one("SELECT 1", &[]).await.unwrap();
+    assert_eq!(row.get::<_, i32>(0), 1);
+}
+
+#[tokio::test]
+async fn statement_name_prefix() {
+    let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config.statement_name_prefix("myapp_");
+    let (client, connection) = config.connect_raw(socket, NoTls).await.unwrap();
+    tokio::spawn(connection);
+
+    let query = "SELECT 'named'";
+    let _stmt = client.prepare(query).await.unwrap();
+
+    let row = client
+        .query_one(
+            "SELECT name FROM pg_prepared_statements WHERE statement = $1",
+            &[&query],
+        )
+        .await
+        .unwrap();
+    let name = row.get::<_, &str>(0);
+    assert!(name.starts_with("myapp_s"), "{}", name);
+}
+
+#[tokio::test]
+async fn pgbouncer_mode() {
+    let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config.pgbouncer_mode(true);
+    let (client, connection) = config.connect_raw(socket, NoTls).await.unwrap();
+    tokio::spawn(connection);
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (id INT, name TEXT);
+             CREATE TYPE pg_temp.mood AS ENUM ('sad', 'happy');",
+        )
+        .await
+        .unwrap();
+
+    // preparing another statement replaces the unnamed statement, but the first must still work
+    let insert = client
+        .prepare("INSERT INTO foo (id, name) VALUES ($1, $2)")
+        .await
+        .unwrap();
+    let select = client
+        .prepare("SELECT name FROM foo WHERE id = $1")
+        .await
+        .unwrap();
+    client.execute(&insert, &[&1i32, &"alice"]).await.unwrap();
+    let row = client.query_one(&select, &[&1i32]).await.unwrap();
+    assert_eq!(row.get::<_, &str>(0), "alice");
+
+    let row = client
+        .query_one("SELECT count(*) FROM pg_prepared_statements", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, i64>(0), 0);
+
+    let err = client.prepare("SELECT 'sad'::mood").await.err().unwrap();
+    assert!(err.to_string().contains("register_types"), "{}", err);
+
+    let oid = client
+        .query_one("SELECT 'pg_temp.mood'::regtype::oid", &[])
+        .await
+        .unwrap()
+        .get::<_, u32>(0);
+    client.register_types(&[Type::new(
+        "mood".to_string(),
+        oid,
+        Kind::Enum(vec!["sad".to_string(), "happy".to_string()]),
+        "pg_temp".to_string(),
+    )]);
+    let row = client.query_one("SELECT 'sad'::mood", &[]).await.unwrap();
+    assert_eq!(row.columns()[0].type_().name(), "mood");
+}
+
+#[tokio::test]
+async fn send_raw() {
+    let client = connect("user=postgres").await;
+
+    let mut buf = BytesMut::new();
+    frontend::query("SELECT 1; SELECT 1/0", &mut buf).unwrap();
+    let messages = client
+        .send_raw(buf.freeze())
+        .await
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    let tags = messages.iter().map(|m| m[0]).collect::<Vec<_>>();
+    assert_eq!(tags, b"TDCEZ");
+
+    // a Sync is required to complete the request
+    let mut buf = BytesMut::new();
+    frontend::parse("", "SELECT 1", None, &mut buf).unwrap();
+    let err = client.send_raw(buf.freeze()).await.err().unwrap();
+    assert!(err.to_string().contains("final message"), "{}", err);
+
+    let mut buf = BytesMut::new();
+    frontend::sync(&mut buf);
+    frontend::sync(&mut buf);
+    client.send_raw(buf.freeze()).await.err().unwrap();
+
+    let row = client.query_one("SELECT 2", &[]).await.unwrap();
+    assert_eq!(row.get::<_, i32>(0), 2);
+}
+
+#[tokio::test]
+async fn send_raw_copy() {
+    let (client, connection) = connect_raw("user=postgres").await.unwrap();
+    let connection = tokio::spawn(connection);
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT)")
+        .await
+        .unwrap();
+
+    // nothing can send the data, so the connection is closed rather than left waiting on it
+    let mut buf = BytesMut::new();
+    frontend::query("COPY foo FROM STDIN", &mut buf).unwrap();
+    let messages = client.send_raw(buf.freeze()).await.unwrap();
+    pin_mut!(messages);
+    assert_eq!(messages.next().await.unwrap().unwrap()[0], b'G');
+    assert!(messages.next().await.unwrap().unwrap_err().is_closed());
+
+    let err = connection.await.unwrap().unwrap_err();
+    assert!(err.to_string().contains("unexpected message"), "{}", err);
+    assert!(client.is_closed());
+}
+
+/// A TLS connector which records whether it was used, and then fails the handshake.
+struct FailingTls(Arc<AtomicBool>);
+
+impl<S> TlsConnect<S> for FailingTls {
+    type Stream = NoTlsStream;
+    type Error = std::io::Error;
+    type Future = future::Ready<Result<NoTlsStream, std::io::Error>>;
+
+    fn connect(self, _: S) -> Self::Future {
+        self.0.store(true, Ordering::SeqCst);
+        future::err(std::io::Error::new(
+            std::io::ErrorKind::ConnectionAborted,
+            "handshake failed",
+        ))
+    }
+}
+
+#[tokio::test]
+async fn ssl_negotiation_direct() {
+    let (client_stream, mut server_stream) = tokio::io::duplex(4096);
+
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config
+        .ssl_mode(SslMode::Require)
+        .ssl_negotiation(SslNegotiation::Direct);
+    let used = Arc::new(AtomicBool::new(false));
+    config
+        .connect_raw(client_stream, FailingTls(used.clone()))
+        .await
+        .err()
+        .unwrap();
+    assert!(used.load(Ordering::SeqCst));
+
+    // the handshake must start without any plaintext SSLRequest
+    let mut sent = vec![];
+    server_stream.read_to_end(&mut sent).await.unwrap();
+    assert!(sent.is_empty(), "{:?}", sent);
+
+    // fallback to plaintext is impossible once the handshake has started
+    let (client_stream, _server_stream) = tokio::io::duplex(4096);
+    config.ssl_mode(SslMode::Prefer);
+    let err = config
+        .connect_raw(client_stream, FailingTls(used))
+        .await
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("sslmode=require"), "{}", err);
+}
+
+#[tokio::test]
+async fn float_datetimes_unsupported() {
+    let (client_stream, server_stream) = tokio::io::duplex(4096);
+
+    // a fake server which accepts the connection and then reports floating point datetimes
+    tokio::spawn(fake_server::run(
+        server_stream,
+        [
+            Step::ReadUntagged,
+            fake_server::messages(|buf| {
+                backend::authentication_ok(buf);
+                backend::parameter_status("integer_datetimes", "off", buf)?;
+                backend::backend_key_data(1, 2, buf);
+                backend::ready_for_query(b'I', buf);
+                Ok(())
+            }),
+        ],
+    ));
+
+    let config = "user=postgres".parse::<Config>().unwrap();
+    let err = config
+        .connect_raw(client_stream, NoTls)
+        .await
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("integer_datetimes"), "{}", err);
+}
+
+#[tokio::test]
+async fn non_utf8_startup_error() {
+    let (client_stream, server_stream) = tokio::io::duplex(4096);
+
+    // a fake server which rejects the connection with a message in LATIN1, as sent before client_encoding applies
+    tokio::spawn(fake_server::run(
+        server_stream,
+        [
+            Step::ReadUntagged,
+            fake_server::messages(|buf| {
+                backend::error_response(
+                    [
+                        (b'S', &b"FATAL"[..]),
+                        (b'C', &b"3D000"[..]),
+                        (b'M', &b"la base de donn\xe9es n'existe pas"[..]),
+                    ],
+                    buf,
+                )
+            }),
+        ],
+    ));
+
+    let config = "user=postgres".parse::<Config>().unwrap();
+    let err = config
+        .connect_raw(client_stream, NoTls)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(err.code(), Some(&SqlState::INVALID_CATALOG_NAME));
+    let db_error = err.as_db_error().unwrap();
+    assert_eq!(db_error.message(), "la base de donn\u{fffd}es n'existe pas");
+}
+
+#[tokio::test]
+async fn negotiate_protocol_version() {
+    async fn connect_negotiating(newest_minor_version: i32, options: &[&str]) -> Result<(), Error> {
+        let (client_stream, server_stream) = tokio::io::duplex(4096);
+
+        let negotiate = fake_server::messages(|buf| {
+            backend::negotiate_protocol_version(newest_minor_version, options.iter().copied(), buf)
+        });
+        tokio::spawn(fake_server::run(
+            server_stream,
+            [Step::ReadUntagged, negotiate, fake_server::accept()],
+        ));
+
+        let config = "user=postgres".parse::<Config>().unwrap();
+        config.connect_raw(client_stream, NoTls).await.map(|_| ())
+    }
+
+    connect_negotiating(0, &[]).await.unwrap();
+
+    let err = connect_negotiating(0, &["_pq_.foo", "_pq_.bar"])
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.to_string(),
+        "server does not support the requested protocol: unrecognized protocol options: _pq_.foo, _pq_.bar"
+    );
+
+    let err = connect_negotiating(2, &[]).await.err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "server does not support the requested protocol: requested protocol version 3.0, but the server offered 3.2"
+    );
+}
+
+#[tokio::test]
+async fn max_pipeline_depth() {
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config.max_pipeline_depth(1);
+    fill_pipeline(config, 1).await;
+}
+
+#[tokio::test]
+async fn max_pipeline_depth_default() {
+    let config = "user=postgres".parse::<Config>().unwrap();
+    assert_eq!(config.get_max_pipeline_depth(), 1024);
+    fill_pipeline(config, 1024).await;
+}
+
+async fn fill_pipeline(config: Config, depth: usize) {
+    let (client_stream, server_stream) = tokio::io::duplex(4096);
+    let (release, released) = futures_channel::oneshot::channel::<()>();
+
+    // a fake server which holds the responses to a full pipeline until the client has given up on more requests
+    let complete = |count| {
+        fake_server::messages(move |buf| {
+            for _ in 0..count {
+                backend::command_complete("SELECT 1", buf)?;
+                backend::ready_for_query(b'I', buf);
+            }
+            Ok(())
+        })
+    };
+    let mut script = vec![Step::ReadUntagged, fake_server::accept()];
+    script.extend((0..depth).map(|_| Step::Read(b'Q')));
+    script.extend([
+        Step::Wait(released),
+        complete(depth),
+        Step::Read(b'Q'),
+        complete(1),
+    ]);
+    let server = tokio::spawn(fake_server::run(server_stream, script));
+
+    let (client, connection) = config.connect_raw(client_stream, NoTls).await.unwrap();
+    let connection = connection.map(|e| e.unwrap());
+    tokio::spawn(connection);
+
+    let mut first = (0..depth)
+        .map(|_| Box::pin(client.batch_execute("SELECT 1")))
+        .collect::<Vec<_>>();
+    // tokio's cooperative budget would otherwise make the later polls pending before they reach the pipeline
+    tokio::task::unconstrained(async {
+        for query in &mut first {
+            assert!(futures_util::poll!(query.as_mut()).is_pending());
+        }
+    })
+    .await;
+
+    // further requests wait for room in the pipeline rather than being queued, so abandoning them sends nothing
+    let second = time::timeout(Duration::from_millis(50), client.batch_execute("SELECT 2")).await;
+    assert!(second.is_err());
+    let mut buf = BytesMut::new();
+    frontend::query("SELECT 2", &mut buf).unwrap();
+    let raw = time::timeout(Duration::from_millis(50), client.send_raw(buf.freeze())).await;
+    assert!(raw.is_err());
+    release.send(()).unwrap();
+    future::try_join_all(first).await.unwrap();
+
+    client.batch_execute("SELECT 3").await.unwrap();
+    drop(client);
+    let read = server.await.unwrap();
+    assert_eq!(read.len(), depth + 2);
+    assert!(read[1..=depth].iter().all(|query| query == b"SELECT 1\0"));
+    assert_eq!(read[depth + 1], b"SELECT 3\0");
+}
+
+/// A stream which counts the `Sync` messages written to it, and can be made to silently drop all traffic.
+struct BlackholeStream {
+    inner: TcpStream,
+    syncs: Arc<AtomicUsize>,
+    blackhole: Arc<AtomicBool>,
+}
+
+impl AsyncRead for BlackholeStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        if self.blackhole.load(Ordering::SeqCst) {
+            return Poll::Pending;
+        }
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for BlackholeStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        if self.blackhole.load(Ordering::SeqCst) {
+            return Poll::Ready(Ok(buf.len()));
+        }
+        if buf == b"S\0\0\0\x04" {
+            self.syncs.fetch_add(1, Ordering::SeqCst);
+        }
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+#[tokio::test]
+async fn idle_ping() {
+    let syncs = Arc::new(AtomicUsize::new(0));
+    let blackhole = Arc::new(AtomicBool::new(false));
+    let stream = BlackholeStream {
+        inner: TcpStream::connect("127.0.0.1:5433").await.unwrap(),
+        syncs: syncs.clone(),
+        blackhole: blackhole.clone(),
+    };
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config.idle_ping_interval(Duration::from_millis(50));
+    let (client, connection) = config.connect_raw(stream, NoTls).await.unwrap();
+    let connection = tokio::spawn(connection);
+
+    client.batch_execute("SELECT 1").await.unwrap();
+    assert_eq!(syncs.load(Ordering::SeqCst), 0);
+
+    time::sleep(Duration::from_millis(300)).await;
+    assert!(syncs.load(Ordering::SeqCst) >= 2);
+    client.batch_execute("SELECT 1").await.unwrap();
+
+    // once the server stops responding, the unanswered ping fails the connection
+    blackhole.store(true, Ordering::SeqCst);
+    let err = time::timeout(Duration::from_secs(5), connection)
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(err.to_string(), "timeout waiting for server");
+}
+
+#[tokio::test]
+async fn socket_read_timeout() {
+    let blackhole = Arc::new(AtomicBool::new(false));
+    let stream = BlackholeStream {
+        inner: TcpStream::connect("127.0.0.1:5433").await.unwrap(),
+        syncs: Arc::new(AtomicUsize::new(0)),
+        blackhole: blackhole.clone(),
+    };
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config.socket_read_timeout(Duration::from_millis(200));
+    let (client, connection) = config.connect_raw(stream, NoTls).await.unwrap();
+    let connection = tokio::spawn(connection);
+
+    // an idle connection doesn't time out
+    client.batch_execute("SELECT 1").await.unwrap();
+    time::sleep(Duration::from_millis(400)).await;
+    client.batch_execute("SELECT pg_sleep(0.05)").await.unwrap();
+
+    blackhole.store(true, Ordering::SeqCst);
+    let err = time::timeout(Duration::from_secs(5), client.batch_execute("SELECT 1"))
+        .await
+        .unwrap()
+        .unwrap_err();
+    assert!(err.is_closed(), "{}", err);
+    let err = connection.await.unwrap().unwrap_err();
+    assert_eq!(err.to_string(), "timeout waiting for server");
+}
+
 #[tokio::test]
 async fn pipelined_prepare() {
     let client = connect("user=postgres").await;
 
-    let prepare1 = client.prepare("SELECT $1::HSTORE[]");
-    let prepare2 = client.prepare("SELECT $1::BIGINT");
+    let prepare1 = client.prepare("SELECT $1::HSTORE[]");
+    let prepare2 = client.prepare("SELECT $1::BIGINT");
+
+    let (statement1, statement2) = try_join!(prepare1, prepare2).unwrap();
+
+    assert_eq!(statement1.params()[0].name(), "_hstore");
+    assert_eq!(statement1.columns()[0].type_().name(), "_hstore");
+
+    assert_eq!(statement2.params()[0], Type::INT8);
+    assert_eq!(statement2.columns()[0].type_(), &Type::INT8);
+}
+
+#[tokio::test]
+async fn prepare_batch() {
+    let mut client = connect("user=postgres").await;
+
+    let statements = client
+        .prepare_batch(&["SELECT $1::HSTORE[]", "SELECT $1::BIGINT", "SELECT 1"])
+        .await
+        .unwrap();
+    assert_eq!(statements.len(), 3);
+    assert_eq!(statements[0].params()[0].name(), "_hstore");
+    assert_eq!(statements[1].params()[0], Type::INT8);
+    assert_eq!(statements[2].columns()[0].type_(), &Type::INT4);
+
+    let row = client.query_one(&statements[1], &[&5i64]).await.unwrap();
+    assert_eq!(row.get::<_, i64>(0), 5);
+
+    let err = client
+        .prepare_batch(&["SELECT 1", "SELECT * FROM missing_table", "SELECT 2"])
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(err.statement_index(), Some(1));
+    assert_eq!(err.code(), Some(&SqlState::UNDEFINED_TABLE));
+    assert!(err.to_string().contains("preparing statement 1"), "{}", err);
+
+    client.batch_execute("SELECT 1").await.unwrap();
+
+    client.__private_api_close();
+    let err = client.prepare_batch(&["SELECT 1"]).await.err().unwrap();
+    assert!(err.is_closed());
+    assert!(err.is_retryable());
+    assert_eq!(err.statement_index(), Some(0));
+}
+
+async fn generic_client_roundtrip<C: GenericClient>(client: &C) {
+    let statements = client
+        .prepare_batch(&["INSERT INTO foo (name) VALUES ($1)", "SELECT name FROM foo"])
+        .await
+        .unwrap();
+    let (rows, notices) = client
+        .execute_with_notices(&statements[0], &[&"jim"])
+        .await
+        .unwrap();
+    assert_eq!(rows, 1);
+    assert!(notices.is_empty());
+
+    let stream = client
+        .build_copy_out("foo")
+        .columns(&["name"])
+        .start()
+        .await
+        .unwrap();
+    let data = stream
+        .try_fold(BytesMut::new(), |mut buf, chunk| async move {
+            buf.extend_from_slice(&chunk);
+            Ok(buf)
+        })
+        .await
+        .unwrap();
+    assert_eq!(&data[..], b"jim\n");
+
+    let messages = client.simple_query("SELECT name FROM foo").await.unwrap();
+    assert!(matches!(messages[0], SimpleQueryMessage::RowDescription(_)));
+
+    let (rows, _) = client
+        .query_with_notices(&statements[1], &[])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    let _ = client.cancel_token();
+
+    let value = StreamingParam::new(&b"bob"[..], 3);
+    let rows = client
+        .execute_streaming("INSERT INTO foo (name) VALUES ($1)", &[], value)
+        .await
+        .unwrap();
+    assert_eq!(rows, 1);
+
+    let info = client.session_info().await.unwrap();
+    assert_eq!(info.current_user(), "postgres");
+}
+
+#[tokio::test]
+async fn generic_client() {
+    let mut client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (name TEXT)")
+        .await
+        .unwrap();
+
+    let transaction = client.transaction().await.unwrap();
+    generic_client_roundtrip(&transaction).await;
+    transaction.rollback().await.unwrap();
+
+    generic_client_roundtrip(&client).await;
+}
+
+#[tokio::test]
+async fn reprepare_deallocated() {
+    let client = connect("user=postgres").await;
+
+    let stmt = client.prepare("SELECT $1::TEXT").await.unwrap();
+    client.batch_execute("DEALLOCATE ALL").await.unwrap();
 
-    let (statement1, statement2) = try_join!(prepare1, prepare2).unwrap();
+    let row = client.query_one(&stmt, &[&"hello"]).await.unwrap();
+    assert_eq!(row.get::<_, &str>(0), "hello");
+    assert_eq!(client.execute(&stmt, &[&"world"]).await.unwrap(), 1);
 
-    assert_eq!(statement1.params()[0].name(), "_hstore");
-    assert_eq!(statement1.columns()[0].type_().name(), "_hstore");
+    client.batch_execute("DEALLOCATE ALL").await.unwrap();
+    client.batch_execute("BEGIN").await.unwrap();
+    let err = client.query(&stmt, &[&"hello"]).await.unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::INVALID_SQL_STATEMENT_NAME));
+    client.batch_execute("ROLLBACK").await.unwrap();
 
-    assert_eq!(statement2.params()[0], Type::INT8);
-    assert_eq!(statement2.columns()[0].type_(), &Type::INT8);
+    let row = client.query_one(&stmt, &[&"again"]).await.unwrap();
+    assert_eq!(row.get::<_, &str>(0), "again");
+}
+
+#[tokio::test]
+async fn reprepare_pipelined() {
+    let client = connect("user=postgres").await;
+
+    let stmt = client.prepare("SELECT $1::TEXT").await.unwrap();
+    client.batch_execute("DEALLOCATE ALL").await.unwrap();
+
+    // the retry would run after the pipelined query, so the error is returned instead
+    let (first, second) = join!(
+        client.query_one(&stmt, &[&"hello"]),
+        client.simple_query("SELECT 1")
+    );
+    let err = first.unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::INVALID_SQL_STATEMENT_NAME));
+    second.unwrap();
+
+    let row = client.query_one(&stmt, &[&"again"]).await.unwrap();
+    assert_eq!(row.get::<_, &str>(0), "again");
+}
+
+#[tokio::test]
+async fn parameter_count_mismatch() {
+    let client = connect("user=postgres").await;
+
+    let err = client
+        .query("SELECT $1::INT, $2::TEXT", &[&1i32])
+        .await
+        .unwrap_err();
+    assert_eq!(err.parameter_counts(), Some((2, 1)));
+    assert_eq!(err.to_string(), "expected 2 parameters but got 1");
+    assert!(err.as_db_error().is_none());
+
+    let stmt = client.prepare("SELECT 1").await.unwrap();
+    let err = client.execute(&stmt, &[&1i32]).await.unwrap_err();
+    assert_eq!(err.parameter_counts(), Some((0, 1)));
+
+    client.batch_execute("SELECT 1").await.unwrap();
+}
+
+#[tokio::test]
+async fn column_metadata() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT, name VARCHAR(10), price NUMERIC(8, 2))")
+        .await
+        .unwrap();
+    let table_oid = client
+        .query_one("SELECT 'foo'::regclass::oid", &[])
+        .await
+        .unwrap()
+        .get::<_, u32>(0);
+
+    let stmt = client
+        .prepare("SELECT id, name, price, 1 AS one FROM foo")
+        .await
+        .unwrap();
+    let columns = stmt.columns();
+
+    assert_eq!(columns[0].table_oid(), Some(table_oid));
+    assert_eq!(columns[0].column_id(), Some(1));
+    assert_eq!(columns[0].type_modifier(), None);
+
+    assert_eq!(columns[1].column_id(), Some(2));
+    assert_eq!(columns[1].type_modifier(), Some(10 + 4));
+
+    assert_eq!(columns[2].column_id(), Some(3));
+    assert_eq!(columns[2].type_modifier(), Some(((8 << 16) | 2) + 4));
+
+    assert_eq!(columns[3].table_oid(), None);
+    assert_eq!(columns[3].column_id(), None);
+    assert_eq!(columns[3].type_modifier(), None);
+}
+
+#[tokio::test]
+async fn no_columns() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE t (id INT)")
+        .await
+        .unwrap();
+
+    // the server responds with NoData rather than a RowDescription
+    let insert = client.prepare("INSERT INTO t VALUES ($1)").await.unwrap();
+    assert!(insert.columns().is_empty());
+    assert_eq!(insert.params(), &[Type::INT4]);
+    let rows = client.query(&insert, &[&1i32]).await.unwrap();
+    assert!(rows.is_empty());
+
+    // the server responds with a RowDescription with no fields
+    let select = client.prepare("SELECT FROM t").await.unwrap();
+    assert!(select.columns().is_empty());
+    let rows = client.query(&select, &[]).await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert!(rows[0].is_empty());
 }
 
 #[tokio::test]
@@ -218,6 +1157,42 @@ async fn custom_enum() {
     );
 }
 
+#[tokio::test]
+async fn register_types() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TYPE pg_temp.mood AS ENUM ('sad', 'ok', 'happy')")
+        .await
+        .unwrap();
+    let oid = client
+        .query_one("SELECT 'pg_temp.mood'::regtype::oid", &[])
+        .await
+        .unwrap()
+        .get::<_, u32>(0);
+
+    // the registered definition is used as-is, so a different name shows it was never loaded from the catalog
+    let registered = Type::new(
+        "registered_mood".to_string(),
+        oid,
+        Kind::Enum(vec![
+            "sad".to_string(),
+            "ok".to_string(),
+            "happy".to_string(),
+        ]),
+        "public".to_string(),
+    );
+    client.register_types(std::slice::from_ref(&registered));
+
+    let select = client.prepare("SELECT $1::mood").await.unwrap();
+    assert_eq!(select.params(), std::slice::from_ref(&registered));
+    assert_eq!(select.columns()[0].type_(), &registered);
+
+    client.clear_type_cache();
+    let select = client.prepare("SELECT $1::mood").await.unwrap();
+    assert_eq!(select.params()[0].name(), "mood");
+}
+
 #[tokio::test]
 async fn custom_domain() {
     let client = connect("user=postgres").await;
@@ -330,6 +1305,13 @@ async fn simple_query() {
         _ => panic!("unexpected message"),
     }
     match &messages[2] {
+        SimpleQueryMessage::RowDescription(columns) => {
+            assert_eq!(columns.first().map(|c| c.name()), Some("id"));
+            assert_eq!(columns.get(1).map(|c| c.name()), Some("name"));
+        }
+        _ => panic!("unexpected message"),
+    }
+    match &messages[3] {
         SimpleQueryMessage::Row(row) => {
             assert_eq!(row.columns().first().map(|c| c.name()), Some("id"));
             assert_eq!(row.columns().get(1).map(|c| c.name()), Some("name"));
@@ -338,7 +1320,7 @@ async fn simple_query() {
         }
         _ => panic!("unexpected message"),
     }
-    match &messages[3] {
+    match &messages[4] {
         SimpleQueryMessage::Row(row) => {
             assert_eq!(row.columns().first().map(|c| c.name()), Some("id"));
             assert_eq!(row.columns().get(1).map(|c| c.name()), Some("name"));
@@ -347,11 +1329,46 @@ async fn simple_query() {
         }
         _ => panic!("unexpected message"),
     }
-    match messages[4] {
+    match messages[5] {
         SimpleQueryMessage::CommandComplete(2) => {}
         _ => panic!("unexpected message"),
     }
-    assert_eq!(messages.len(), 5);
+    assert_eq!(messages.len(), 6);
+}
+
+#[tokio::test]
+async fn simple_query_result_sets() {
+    let client = connect("user=postgres").await;
+
+    let messages = client
+        .simple_query("SELECT 1 AS a; SELECT 'x' AS b, 2 AS c WHERE false; SELECT 3 AS d")
+        .await
+        .unwrap();
+
+    let mut result_sets = vec![];
+    for message in &messages {
+        match message {
+            SimpleQueryMessage::RowDescription(columns) => {
+                let names = columns.iter().map(|c| c.name().to_string()).collect();
+                result_sets.push((names, vec![]));
+            }
+            SimpleQueryMessage::Row(row) => {
+                let (_, rows) = result_sets.last_mut().unwrap();
+                rows.push(row.get(0).unwrap().to_string());
+            }
+            SimpleQueryMessage::CommandComplete(_) => {}
+            _ => panic!("unexpected message"),
+        }
+    }
+
+    assert_eq!(
+        result_sets,
+        [
+            (vec!["a".to_string()], vec!["1".to_string()]),
+            (vec!["b".to_string(), "c".to_string()], vec![]),
+            (vec!["d".to_string()], vec!["3".to_string()]),
+        ]
+    );
 }
 
 #[tokio::test]
@@ -602,6 +1619,82 @@ async fn copy_in() {
     assert_eq!(rows[1].get::<_, &str>(1), "joe");
 }
 
+#[tokio::test]
+async fn copy_in_forward() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INTEGER)")
+        .await
+        .unwrap();
+
+    let stream = stream::iter((0..100).map(|i| Ok::<_, Error>(Bytes::from(format!("{}\n", i)))));
+    let sink = client.copy_in("COPY foo FROM STDIN").await.unwrap();
+    pin_mut!(sink);
+    stream.forward(sink.as_mut()).await.unwrap();
+    assert_eq!(sink.finish().await.unwrap(), 100);
+
+    let count = client
+        .query_one("SELECT COUNT(*) FROM foo", &[])
+        .await
+        .unwrap()
+        .get::<_, i64>(0);
+    assert_eq!(count, 100);
+}
+
+#[tokio::test]
+async fn copy_builder_csv() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE \"copy; builder\" (
+                id SERIAL,
+                \"Name\" TEXT,
+                note TEXT
+            )",
+        )
+        .await
+        .unwrap();
+
+    let sink = client
+        .build_copy_in("copy; builder")
+        .columns(&["Name", "note"])
+        .format(CopyFormat::Csv)
+        .header(true)
+        .delimiter('|')
+        .null("<null>")
+        .start()
+        .await
+        .unwrap();
+    pin_mut!(sink);
+    sink.send(Bytes::from_static(
+        b"Name|note\njim|<null>\n'joe'|\"a|b\"\n",
+    ))
+    .await
+    .unwrap();
+    let rows = sink.finish().await.unwrap();
+    assert_eq!(rows, 2);
+
+    let stream = client
+        .build_copy_out("copy; builder")
+        .schema("pg_temp")
+        .columns(&["Name", "note"])
+        .format(CopyFormat::Csv)
+        .null("<null>")
+        .start()
+        .await
+        .unwrap();
+    let data = stream
+        .try_fold(BytesMut::new(), |mut buf, chunk| async move {
+            buf.extend_from_slice(&chunk);
+            Ok(buf)
+        })
+        .await
+        .unwrap();
+    assert_eq!(&data[..], b"jim,<null>\n'joe',a|b\n");
+}
+
 #[tokio::test]
 async fn copy_in_large() {
     let client = connect("user=postgres").await;
@@ -665,6 +1758,41 @@ async fn copy_in_error() {
     assert_eq!(rows.len(), 0);
 }
 
+#[tokio::test]
+async fn copy_in_simple() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INTEGER)")
+        .await
+        .unwrap();
+
+    let sink = client.copy_in_simple("COPY foo FROM STDIN").await.unwrap();
+    pin_mut!(sink);
+    sink.send(Bytes::from_static(b"1\n")).await.unwrap();
+    assert_eq!(sink.finish().await.unwrap(), 1);
+
+    // an aborted copy must leave the connection usable too
+    {
+        let sink = client.copy_in_simple("COPY foo FROM STDIN").await.unwrap();
+        pin_mut!(sink);
+        sink.send(Bytes::from_static(b"2\n")).await.unwrap();
+    }
+
+    let count = client
+        .query_one("SELECT COUNT(*) FROM foo", &[])
+        .await
+        .unwrap()
+        .get::<_, i64>(0);
+    assert_eq!(count, 1);
+    let count = client
+        .query_one("SELECT COUNT(*) FROM foo", &[])
+        .await
+        .unwrap()
+        .get::<_, i64>(0);
+    assert_eq!(count, 1);
+}
+
 #[tokio::test]
 async fn copy_out() {
     let client = connect("user=postgres").await;
@@ -735,6 +1863,53 @@ async fn notices() {
     );
 }
 
+#[tokio::test]
+async fn query_with_notices() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (id INT);
+             CREATE FUNCTION pg_temp.noisy(n INT) RETURNS INT AS $$
+             BEGIN
+                 RAISE NOTICE 'noisy %', n;
+                 RETURN n;
+             END;
+             $$ LANGUAGE plpgsql;",
+        )
+        .await
+        .unwrap();
+
+    let stmt = client.prepare("SELECT pg_temp.noisy($1)").await.unwrap();
+    let (first, second, unrelated) = join!(
+        client.query_with_notices(&stmt, &[&1]),
+        client.query_with_notices(&stmt, &[&2]),
+        client.query(&stmt, &[&3]),
+    );
+    unrelated.unwrap();
+
+    let (rows, notices) = first.unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+    let messages = notices.iter().map(|n| n.message()).collect::<Vec<_>>();
+    assert_eq!(messages, ["noisy 1"]);
+
+    let (rows, notices) = second.unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 2);
+    let messages = notices.iter().map(|n| n.message()).collect::<Vec<_>>();
+    assert_eq!(messages, ["noisy 2"]);
+
+    let (rows, notices) = client
+        .execute_with_notices(
+            "INSERT INTO foo SELECT pg_temp.noisy(i) FROM generate_series(4, 5) i",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows, 2);
+    let messages = notices.iter().map(|n| n.message()).collect::<Vec<_>>();
+    assert_eq!(messages, ["noisy 4", "noisy 5"]);
+}
+
 #[tokio::test]
 async fn notifications() {
     let (client, mut connection) = connect_raw("user=postgres").await.unwrap();
@@ -954,3 +2129,190 @@ async fn deferred_constraint() {
         .await
         .unwrap_err();
 }
+
+#[tokio::test]
+async fn query_rows_affected() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id SERIAL, name TEXT)")
+        .await
+        .unwrap();
+
+    let stream = client
+        .query_raw::<_, &str, _>(
+            "INSERT INTO foo (name) VALUES ('alice'), ('bob') RETURNING id",
+            [],
+        )
+        .await
+        .unwrap();
+    pin_mut!(stream);
+    assert_eq!(stream.rows_affected(), None);
+
+    let rows = stream.as_mut().try_collect::<Vec<_>>().await.unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(stream.rows_affected(), Some(2));
+}
+
+#[tokio::test]
+async fn query_timings() {
+    let client = connect("user=postgres").await;
+
+    let stream = client
+        .query_raw::<_, &str, _>("SELECT x FROM pg_sleep(0.05), generate_series(1, 3) x", [])
+        .await
+        .unwrap();
+    pin_mut!(stream);
+
+    let timings = stream.timings();
+    assert!(timings.first_row_at().is_none());
+    assert!(timings.completed_at().is_none());
+
+    stream.as_mut().try_collect::<Vec<_>>().await.unwrap();
+    let timings = stream.timings();
+    let sent_at = timings.sent_at().unwrap();
+    let first_row_at = timings.first_row_at().unwrap();
+    let completed_at = timings.completed_at().unwrap();
+    assert!(first_row_at - sent_at >= Duration::from_millis(50));
+    assert!(completed_at >= first_row_at);
+}
+
+#[tokio::test]
+async fn empty_query() {
+    let client = connect("user=postgres").await;
+
+    assert_eq!(client.execute("-- comment", &[]).await.unwrap(), 0);
+    assert!(client.query("-- comment", &[]).await.unwrap().is_empty());
+
+    let stream = client
+        .query_raw::<_, &str, _>("-- comment", [])
+        .await
+        .unwrap();
+    pin_mut!(stream);
+    assert!(!stream.is_empty_query());
+    assert!(stream.as_mut().try_next().await.unwrap().is_none());
+    assert!(stream.is_empty_query());
+    assert_eq!(stream.rows_affected(), Some(0));
+
+    let stream = client
+        .query_raw::<_, &str, _>("SELECT 1", [])
+        .await
+        .unwrap();
+    pin_mut!(stream);
+    stream.as_mut().try_collect::<Vec<_>>().await.unwrap();
+    assert!(!stream.is_empty_query());
+
+    let messages = client.simple_query("-- comment").await.unwrap();
+    match messages[..] {
+        [SimpleQueryMessage::CommandComplete(0)] => {}
+        _ => panic!("unexpected messages: {:?}", messages),
+    }
+}
+
+#[tokio::test]
+async fn execute_streaming() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT, data BYTEA)")
+        .await
+        .unwrap();
+
+    let data = (0..100_000).map(|i| i as u8).collect::<Vec<_>>();
+    let value = StreamingParam::new(std::io::Cursor::new(data.clone()), data.len() as u32);
+    let rows = client
+        .execute_streaming(
+            "INSERT INTO foo (id, data) VALUES ($1, $2)",
+            &[&1i32],
+            value,
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows, 1);
+
+    let row = client
+        .query_one("SELECT data FROM foo WHERE id = 1", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, &[u8]>(0), &data[..]);
+
+    let data = data.into_iter().rev().collect::<Vec<_>>();
+    let value = StreamingParam::new(std::io::Cursor::new(data.clone()), data.len() as u32);
+    let rows = client
+        .execute_streaming("UPDATE foo SET data = $2 WHERE id = $1", &[&1i32], value)
+        .await
+        .unwrap();
+    assert_eq!(rows, 1);
+
+    let row = client
+        .query_one("SELECT data FROM foo WHERE id = 1", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, &[u8]>(0), &data[..]);
+
+    // a reader which runs out early fails the statement but leaves the connection usable
+    let value = StreamingParam::new(&b"short"[..], 100_000);
+    let err = client
+        .execute_streaming(
+            "INSERT INTO foo (id, data) VALUES ($1, $2)",
+            &[&2i32],
+            value,
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("parameter 1"), "{}", err);
+
+    let count = client
+        .query_one("SELECT COUNT(*) FROM foo", &[])
+        .await
+        .unwrap()
+        .get::<_, i64>(0);
+    assert_eq!(count, 1);
+
+    // the other parameters may be null
+    let value = StreamingParam::new(&b"null id"[..], 7);
+    let rows = client
+        .execute_streaming(
+            "INSERT INTO foo (id, data) VALUES ($1, $2)",
+            &[&None::<i32>],
+            value,
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows, 1);
+
+    let row = client
+        .query_one("SELECT data FROM foo WHERE id IS NULL", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, &[u8]>(0), b"null id");
+}
+
+#[tokio::test]
+async fn close_reason() {
+    let (client, connection) = connect_raw("user=postgres").await.unwrap();
+    let connection = tokio::spawn(connection);
+
+    let other = connect("user=postgres").await;
+    let pid = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get::<_, i32>(0);
+    assert!(client.close_reason().is_none());
+
+    other
+        .execute("SELECT pg_terminate_backend($1)", &[&pid])
+        .await
+        .unwrap();
+
+    let err = connection.await.unwrap().unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::ADMIN_SHUTDOWN));
+
+    let reason = client.close_reason().unwrap();
+    assert_eq!(reason.code(), &SqlState::ADMIN_SHUTDOWN);
+
+    let err = client.simple_query("SELECT 1").await.unwrap_err();
+    assert!(err.is_closed());
+    assert_eq!(err.code(), Some(&SqlState::ADMIN_SHUTDOWN));
+}
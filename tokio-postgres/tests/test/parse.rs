@@ -1,5 +1,5 @@
 use std::time::Duration;
-use tokio_postgres::config::{Config, TargetSessionAttrs};
+use tokio_postgres::config::{Config, SslMode, SslNegotiation, TargetSessionAttrs};
 
 fn check(s: &str, config: &Config) {
     assert_eq!(s.parse::<Config>().expect(s), *config, "`{}`", s);
@@ -16,6 +16,58 @@ fn pairs_ok() {
     );
 }
 
+#[test]
+fn default_transaction_read_only() {
+    for s in ["on", "true", "yes", "1", "ON", "True"] {
+        check(
+            &format!("user=foo default_transaction_read_only={}", s),
+            Config::new()
+                .user("foo")
+                .default_transaction_read_only(true),
+        );
+    }
+    for s in ["off", "false", "no", "0", "OFF"] {
+        check(
+            &format!("user=foo default_transaction_read_only={}", s),
+            Config::new().user("foo"),
+        );
+    }
+    assert!("default_transaction_read_only=maybe"
+        .parse::<Config>()
+        .is_err());
+}
+
+#[test]
+fn ssl_mode() {
+    for (s, mode) in [
+        ("disable", SslMode::Disable),
+        ("allow", SslMode::Allow),
+        ("prefer", SslMode::Prefer),
+        ("require", SslMode::Require),
+        ("verify-ca", SslMode::VerifyCa),
+        ("verify-full", SslMode::VerifyFull),
+    ] {
+        check(&format!("sslmode={}", s), Config::new().ssl_mode(mode));
+    }
+    let err = "sslmode=verify".parse::<Config>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid connection string: invalid value for option `sslmode`"
+    );
+}
+
+#[test]
+fn ssl_negotiation() {
+    check(
+        "sslmode=require sslnegotiation=direct",
+        Config::new()
+            .ssl_mode(SslMode::Require)
+            .ssl_negotiation(SslNegotiation::Direct),
+    );
+    check("sslnegotiation=postgres", &Config::new());
+    assert!("sslnegotiation=indirect".parse::<Config>().is_err());
+}
+
 #[test]
 fn pairs_ws() {
     check(
@@ -54,6 +106,18 @@ fn keepalive_settings() {
             .keepalives_interval(Duration::from_secs(5))
             .keepalives_retries(9),
     );
+    check(
+        "keepalives=1 keepalives_idle=15 keepalives_interval=5 keepalives_count=9",
+        Config::new()
+            .keepalives(true)
+            .keepalives_idle(Duration::from_secs(15))
+            .keepalives_interval(Duration::from_secs(5))
+            .keepalives_retries(9),
+    );
+    check(
+        "keepalives_idle=0 keepalives_interval=0 keepalives_count=0",
+        &Config::new(),
+    );
 }
 
 #[test]
@@ -32,7 +32,7 @@ async fn test_replication() {
         .simple_query("SELECT 'test_logical_replication'::regclass::oid")
         .await
         .unwrap();
-    let rel_id: u32 = if let Row(row) = &res[0] {
+    let rel_id: u32 = if let Row(row) = &res[1] {
         row.get("oid").unwrap().parse().unwrap()
     } else {
         panic!("unexpeced query message");
@@ -54,7 +54,7 @@ async fn test_replication() {
         slot
     );
     let slot_query = client.simple_query(&query).await.unwrap();
-    let lsn = if let Row(row) = &slot_query[0] {
+    let lsn = if let Row(row) = &slot_query[1] {
         row.get("consistent_point").unwrap()
     } else {
         panic!("unexpeced query message");
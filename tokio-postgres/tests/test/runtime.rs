@@ -1,8 +1,16 @@
+use crate::fake_server::{self, backend, Step};
+use bytes::BytesMut;
 use futures_util::{join, FutureExt};
-use std::time::Duration;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+#[cfg(unix)]
+use tokio::net::UnixListener;
+use tokio::net::{TcpListener, TcpStream};
 use tokio::time;
+use tokio_postgres::config::{RetryPolicy, TargetSessionAttrs};
 use tokio_postgres::error::SqlState;
-use tokio_postgres::{Client, NoTls};
+use tokio_postgres::{Client, Config, NoTls};
 
 async fn connect(s: &str) -> Client {
     let (client, connection) = tokio_postgres::connect(s, NoTls).await.unwrap();
@@ -26,6 +34,61 @@ async fn unix_socket() {
     smoke_test("host=/var/run/postgresql port=5433 user=postgres").await;
 }
 
+#[cfg(unix)]
+#[tokio::test]
+async fn require_peer() {
+    // the server's socket isn't reachable from the tests, so a fake one stands in, which runs as the current user
+    let dir = std::env::temp_dir().join(format!(
+        "tokio-postgres-require-peer-{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(".s.PGSQL.5433");
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+
+    tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        fake_server::run(
+            socket,
+            [
+                Step::ReadUntagged,
+                fake_server::accept(),
+                Step::Read(b'Q'),
+                fake_server::messages(|buf| {
+                    backend::command_complete("SELECT 1", buf)?;
+                    backend::ready_for_query(b'I', buf);
+                    Ok(())
+                }),
+            ],
+        )
+        .await;
+    });
+
+    let host = dir.to_str().unwrap();
+    let client = connect(&format!(
+        "host={} port=5433 user=postgres requirepeer={}",
+        host,
+        whoami::username()
+    ))
+    .await;
+    client.batch_execute("SELECT 1").await.unwrap();
+
+    let err = tokio_postgres::connect(
+        &format!(
+            "host={} port=5433 user=postgres requirepeer=tokio_postgres_no_such_user",
+            host
+        ),
+        NoTls,
+    )
+    .await
+    .err()
+    .unwrap();
+    assert!(err.to_string().contains("requirepeer"), "{}", err);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
 #[tokio::test]
 async fn tcp() {
     smoke_test("host=localhost port=5433 user=postgres").await;
@@ -41,6 +104,12 @@ async fn multiple_hosts_multiple_ports() {
     smoke_test("host=foobar.invalid,localhost port=5432,5433 user=postgres").await;
 }
 
+#[cfg(feature = "srv")]
+#[tokio::test]
+async fn srv_lookup_fallback() {
+    smoke_test("host=localhost port=5433 user=postgres srv_lookup=on").await;
+}
+
 #[tokio::test]
 async fn wrong_port_count() {
     tokio_postgres::connect("host=localhost port=5433,5433 user=postgres", NoTls)
@@ -118,6 +187,358 @@ async fn hostaddr_host_both_missing() {
     .unwrap();
 }
 
+#[tokio::test]
+async fn tcp_nodelay_disabled() {
+    let (client, connection) = Config::new()
+        .host("localhost")
+        .port(5433)
+        .user("postgres")
+        .tcp_nodelay(false)
+        .connect(NoTls)
+        .await
+        .unwrap();
+    tokio::spawn(connection.map(|e| e.unwrap()));
+
+    let rows = client.query("SELECT 1::INT", &[]).await.unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+}
+
+#[tokio::test]
+async fn connect_error_context() {
+    let config = "host=localhost,127.0.0.1 port=1 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    let err = config.connect(NoTls).await.err().unwrap();
+
+    assert_eq!(err.host(), Some("127.0.0.1:1"));
+    assert!(err
+        .to_string()
+        .starts_with("error connecting to server at 127.0.0.1:1: "));
+    assert!(err.is_retryable());
+
+    let previous = err.previous_attempts();
+    assert!(!previous.is_empty());
+    for attempt in previous {
+        assert!(attempt.host().unwrap().starts_with("localhost ("));
+        assert!(attempt.previous_attempts().is_empty());
+    }
+}
+
+#[tokio::test]
+async fn dns_cache() {
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.dns_cache_ttl(Duration::from_secs(60));
+    assert_eq!(config.get_dns_cache_ttl(), Some(Duration::from_secs(60)));
+
+    let (client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection.map(|e| e.unwrap()));
+    client.batch_execute("SELECT 1").await.unwrap();
+    assert!(config
+        .__private_api_cached_addrs("localhost", 5433)
+        .is_some());
+
+    // a name that doesn't resolve can only be connected to through the cache
+    let localhost = "127.0.0.1:5433".parse().unwrap();
+    let mut config = "host=dns-cache.invalid port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.dns_cache_ttl(Duration::from_secs(60));
+    config.__private_api_cache_addrs("dns-cache.invalid", 5433, vec![localhost]);
+    let (client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection.map(|e| e.unwrap()));
+    client.batch_execute("SELECT 1").await.unwrap();
+
+    // the server refusing the session doesn't invalidate the entry
+    let mut read_only = config.clone();
+    read_only.target_session_attrs(TargetSessionAttrs::ReadOnly);
+    let err = read_only.connect(NoTls).await.err().unwrap();
+    assert!(err.to_string().contains("not read only"), "{}", err);
+    assert_eq!(
+        config.__private_api_cached_addrs("dns-cache.invalid", 5433),
+        Some(vec![localhost])
+    );
+
+    // but an address which can't be reached does
+    let port = TcpListener::bind("127.0.0.1:0")
+        .await
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let mut config = format!("host=dns-cache.invalid port={} user=postgres", port)
+        .parse::<Config>()
+        .unwrap();
+    config.dns_cache_ttl(Duration::from_secs(60));
+    config.__private_api_cache_addrs("dns-cache.invalid", port, vec![localhost]);
+    config.connect(NoTls).await.err().unwrap();
+    assert_eq!(
+        config.__private_api_cached_addrs("dns-cache.invalid", port),
+        None
+    );
+}
+
+#[tokio::test]
+async fn configure_socket() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.configure_socket({
+        let calls = calls.clone();
+        move |socket| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            socket.set_recv_buffer_size(256 * 1024)
+        }
+    });
+
+    let (client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection.map(|e| e.unwrap()));
+    client.batch_execute("SELECT 1").await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    client.cancel_token().cancel_query(NoTls).await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.configure_socket(|_| {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            "not allowed",
+        ))
+    });
+    let err = config.connect(NoTls).await.err().unwrap();
+    assert!(err.to_string().contains("not allowed"), "{}", err);
+}
+
+#[tokio::test]
+#[ignore] // FIXME doesn't work with our docker-based tests :(
+async fn configure_socket_unix() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut config = "host=/tmp port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.configure_socket({
+        let calls = calls.clone();
+        move |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    });
+    let (client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection.map(|e| e.unwrap()));
+    client.batch_execute("SELECT 1").await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn auto_reprepare() {
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+
+    for auto_reprepare in [false, true] {
+        config.auto_reprepare(auto_reprepare);
+        let (client, connection) = config.connect(NoTls).await.unwrap();
+        tokio::spawn(connection.map(|e| e.unwrap()));
+
+        client
+            .batch_execute("CREATE TEMPORARY TABLE foo (id INT); INSERT INTO foo VALUES (1)")
+            .await
+            .unwrap();
+        let stmt = client.prepare("SELECT * FROM foo").await.unwrap();
+        client
+            .batch_execute("ALTER TABLE foo ADD COLUMN name TEXT DEFAULT 'bob'")
+            .await
+            .unwrap();
+
+        let result = client.query_one(&stmt, &[]).await;
+        if auto_reprepare {
+            let row = result.unwrap();
+            assert_eq!(row.get::<_, i32>(0), 1);
+            assert_eq!(row.get::<_, &str>(1), "bob");
+            assert_eq!(stmt.columns().len(), 2);
+            assert_eq!(stmt.columns()[1].name(), "name");
+        } else {
+            let err = result.unwrap_err();
+            let err = err.as_db_error().unwrap();
+            assert_eq!(*err.code(), SqlState::FEATURE_NOT_SUPPORTED);
+            assert_eq!(err.routine(), Some("RevalidateCachedQuery"));
+        }
+    }
+}
+
+#[tokio::test]
+async fn connect_with_retry_ok() {
+    let config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    let (client, connection) = config
+        .connect_with_retry(NoTls, RetryPolicy::new())
+        .await
+        .unwrap();
+    tokio::spawn(connection.map(|e| e.unwrap()));
+
+    client.batch_execute("SELECT 1").await.unwrap();
+}
+
+#[tokio::test]
+async fn connect_with_retry_exhausted() {
+    let config = "host=127.0.0.1 port=1 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    let policy = RetryPolicy::new()
+        .max_attempts(3)
+        .initial_backoff(Duration::from_millis(20));
+
+    let start = Instant::now();
+    let err = config
+        .connect_with_retry(NoTls, policy)
+        .await
+        .err()
+        .unwrap();
+    assert!(err.is_retryable());
+    // two backoffs: 20ms + 40ms
+    assert!(start.elapsed() >= Duration::from_millis(60));
+}
+
+#[tokio::test]
+async fn connect_with_retry_fails_fast() {
+    let config = "host=localhost port=5433 user=pass_user password=foo dbname=postgres"
+        .parse::<Config>()
+        .unwrap();
+    let policy = RetryPolicy::new().initial_backoff(Duration::from_secs(10));
+
+    let err = config
+        .connect_with_retry(NoTls, policy)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(err.code(), Some(&SqlState::INVALID_PASSWORD));
+    assert!(!err.is_retryable());
+}
+
+#[tokio::test]
+async fn connect_with_retry_starting_up() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    tokio::spawn(async move {
+        // reject the first attempt the way a server which hasn't finished recovery does
+        let (socket, _) = listener.accept().await.unwrap();
+        fake_server::run(
+            socket,
+            [
+                Step::ReadUntagged,
+                fake_server::messages(|buf| {
+                    backend::error_response(
+                        [
+                            (b'S', "FATAL"),
+                            (b'V', "FATAL"),
+                            (b'C', "57P03"),
+                            (b'M', "the database system is starting up"),
+                        ],
+                        buf,
+                    )
+                }),
+            ],
+        )
+        .await;
+
+        // then let the next one through to the real server
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut server = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+        let _ = tokio::io::copy_bidirectional(&mut socket, &mut server).await;
+    });
+
+    let config = format!("host=127.0.0.1 port={} user=postgres", port)
+        .parse::<Config>()
+        .unwrap();
+    let policy = RetryPolicy::new()
+        .max_attempts(2)
+        .initial_backoff(Duration::from_millis(20));
+    let (client, connection) = config.connect_with_retry(NoTls, policy).await.unwrap();
+    tokio::spawn(connection.map(|e| e.unwrap()));
+
+    client.batch_execute("SELECT 1").await.unwrap();
+}
+
+#[tokio::test]
+async fn ssl_mode_allow() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let server = tokio::spawn(async move {
+        // reject the plaintext attempt the way a server which requires TLS does
+        let (socket, _) = listener.accept().await.unwrap();
+        fake_server::run(
+            socket,
+            [
+                Step::ReadUntagged,
+                fake_server::messages(|buf| {
+                    backend::error_response(
+                        [(b'S', "FATAL"), (b'C', "28000"), (b'M', "no encryption")],
+                        buf,
+                    )
+                }),
+            ],
+        )
+        .await;
+
+        // the retry starts by asking for TLS
+        let (socket, _) = listener.accept().await.unwrap();
+        fake_server::run(
+            socket,
+            [Step::ReadUntagged, Step::Write(BytesMut::from(&b"N"[..]))],
+        )
+        .await
+    });
+
+    let config = format!("host=127.0.0.1 port={} user=postgres sslmode=allow", port)
+        .parse::<Config>()
+        .unwrap();
+    let err = config.connect(NoTls).await.err().unwrap();
+    assert!(
+        err.to_string().contains("server does not support TLS"),
+        "{}",
+        err
+    );
+    assert_eq!(server.await.unwrap(), [[0x04, 0xd2, 0x16, 0x2f]]);
+
+    // other errors aren't about TLS, so there's no retry
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        fake_server::run(
+            socket,
+            [
+                Step::ReadUntagged,
+                fake_server::messages(|buf| {
+                    backend::error_response(
+                        [(b'S', "FATAL"), (b'C', "3D000"), (b'M', "no such database")],
+                        buf,
+                    )
+                }),
+            ],
+        )
+        .await;
+        time::timeout(Duration::from_millis(100), listener.accept())
+            .await
+            .is_err()
+    });
+
+    let config = format!("host=127.0.0.1 port={} user=postgres sslmode=allow", port)
+        .parse::<Config>()
+        .unwrap();
+    let err = config.connect(NoTls).await.err().unwrap();
+    assert_eq!(err.code(), Some(&SqlState::INVALID_CATALOG_NAME));
+    assert!(server.await.unwrap());
+}
+
 #[tokio::test]
 async fn cancel_query() {
     let client = connect("host=localhost port=5433 user=postgres").await;
@@ -133,3 +554,61 @@ async fn cancel_query() {
         t => panic!("unexpected return: {:?}", t),
     }
 }
+
+#[tokio::test]
+async fn query_deadline() {
+    let client = connect("host=localhost port=5433 user=postgres").await;
+
+    let deadline = Instant::now() + Duration::from_millis(100);
+    let err = client
+        .query_deadline("SELECT pg_sleep(100)", &[], deadline, NoTls)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), None);
+    assert_eq!(err.to_string(), "timeout waiting for server");
+
+    let rows = client.query("SELECT 1::INT", &[]).await.unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let rows = client
+        .query_deadline("SELECT 2::INT", &[], deadline, NoTls)
+        .await
+        .unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 2);
+
+    // preparing waits on the lock, and the statement isn't executed once the lock is released
+    let locker = connect("host=localhost port=5433 user=postgres").await;
+    locker
+        .batch_execute(
+            "DROP TABLE IF EXISTS query_deadline_locked;
+             CREATE TABLE query_deadline_locked (id INT)",
+        )
+        .await
+        .unwrap();
+    locker
+        .batch_execute("BEGIN; LOCK TABLE query_deadline_locked IN ACCESS EXCLUSIVE MODE")
+        .await
+        .unwrap();
+    let deadline = Instant::now() + Duration::from_millis(100);
+    let err = client
+        .query_deadline(
+            "INSERT INTO query_deadline_locked VALUES (1)",
+            &[],
+            deadline,
+            NoTls,
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "timeout waiting for server");
+    locker.batch_execute("ROLLBACK").await.unwrap();
+    let row = client
+        .query_one("SELECT count(*) FROM query_deadline_locked", &[])
+        .await
+        .unwrap();
+    locker
+        .batch_execute("DROP TABLE query_deadline_locked")
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, i64>(0), 0);
+}
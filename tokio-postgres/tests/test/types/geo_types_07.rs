@@ -37,6 +37,25 @@ async fn test_box_params() {
     .await;
 }
 
+#[tokio::test]
+async fn test_box_array_params() {
+    test_type(
+        "BOX[]",
+        &[
+            (
+                Some(vec![
+                    Rect::new(Coord { x: 0., y: 0. }, Coord { x: 1., y: 2. }),
+                    Rect::new(Coord { x: -3.2, y: 1.618 }, Coord { x: 3., y: 5. }),
+                ]),
+                "'{(1,2),(0,0);(3,5),(-3.2,1.618)}'",
+            ),
+            (Some(vec![]), "'{}'"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+}
+
 #[tokio::test]
 async fn test_path_params() {
     let points = vec![
@@ -8,7 +8,9 @@ use std::net::IpAddr;
 use std::result;
 use std::str::FromStr;
 use std::time::{Duration, UNIX_EPOCH};
-use tokio_postgres::types::{FromSql, FromSqlOwned, IsNull, Kind, PgLsn, ToSql, Type, WrongType};
+use tokio_postgres::types::{
+    ArrayIter, FromSql, FromSqlOwned, IsNull, Kind, Money, ObjectId, PgLsn, ToSql, Type, WrongType,
+};
 
 use crate::connect;
 use bytes::BytesMut;
@@ -74,6 +76,32 @@ async fn test_i8_params() {
     test_type("\"char\"", &[(Some('a' as i8), "'a'"), (None, "NULL")]).await;
 }
 
+#[tokio::test]
+async fn test_i8_catalog_columns() {
+    let client = connect("user=postgres").await;
+
+    let row = client
+        .query_one(
+            "SELECT c.relkind, t.typtype FROM pg_class c JOIN pg_type t ON t.oid = c.reltype \
+             WHERE c.oid = 'pg_class'::regclass",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.columns()[0].type_(), &Type::CHAR);
+    assert_eq!(row.get::<_, i8>(0), b'r' as i8);
+    assert_eq!(row.get::<_, i8>(1), b'c' as i8);
+
+    let row = client
+        .query_one(
+            "SELECT count(*) FROM pg_class WHERE relkind = $1",
+            &[&(b'r' as i8)],
+        )
+        .await
+        .unwrap();
+    assert!(row.get::<_, i64>(0) > 0);
+}
+
 #[tokio::test]
 async fn test_name_params() {
     test_type(
@@ -129,6 +157,56 @@ async fn test_oid_params() {
     .await;
 }
 
+#[tokio::test]
+async fn test_money_params() {
+    test_type(
+        "MONEY",
+        &[
+            (Some(Money(0)), "'0'"),
+            (Some(Money(12_345)), "'123.45'"),
+            (
+                Some(Money(-9_223_372_036_854_775_808)),
+                "'-92233720368547758.08'",
+            ),
+            (
+                Some(Money(9_223_372_036_854_775_807)),
+                "'92233720368547758.07'",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_object_id_params() {
+    test_type(
+        "OID",
+        &[
+            (Some(ObjectId::from(2_147_483_548)), "2147483548"),
+            (Some(ObjectId::from(4_000_000_000)), "4000000000"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_object_id_reg_types() {
+    let client = connect("user=postgres").await;
+
+    let row = client
+        .query_one(
+            "SELECT 'pg_class'::regclass, 'int4'::regtype, $1::regclass::text",
+            &[&ObjectId::from(1259)],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, ObjectId>(0), ObjectId::from(1259));
+    assert_eq!(row.get::<_, ObjectId>(1).get(), Type::INT4.oid());
+    assert_eq!(row.get::<_, &str>(2), "pg_class");
+}
+
 #[tokio::test]
 async fn test_i64_params() {
     test_type(
@@ -154,6 +232,24 @@ async fn test_lsn_params() {
     .await
 }
 
+#[tokio::test]
+async fn test_lsn_current_wal() {
+    let client = connect("user=postgres").await;
+
+    let row = client
+        .query_one("SELECT lsn, lsn::text FROM pg_current_wal_lsn() lsn", &[])
+        .await
+        .unwrap();
+    let lsn = row.get::<_, PgLsn>(0);
+    assert_eq!(lsn.to_string(), row.get::<_, &str>(1));
+
+    let row = client
+        .query_one("SELECT $1::pg_lsn <= pg_current_wal_lsn()", &[&lsn])
+        .await
+        .unwrap();
+    assert!(row.get::<_, bool>(0));
+}
+
 #[tokio::test]
 async fn test_f32_params() {
     test_type(
@@ -324,6 +420,23 @@ async fn test_borrowed_bytea() {
     assert_eq!(s, b"foo");
 }
 
+#[tokio::test]
+async fn test_shared_bytea() {
+    let client = connect("user=postgres").await;
+    let stmt = client
+        .prepare("SELECT 'foo'::BYTEA, NULL::BYTEA, 'bar'::TEXT")
+        .await
+        .unwrap();
+    let rows = client.query(&stmt, &[]).await.unwrap();
+    let b = rows[0].get_bytes(0).unwrap();
+    drop(rows);
+    assert_eq!(b, &b"foo"[..]);
+
+    let rows = client.query(&stmt, &[]).await.unwrap();
+    assert_eq!(rows[0].try_get_bytes(1).unwrap(), None);
+    rows[0].try_get_bytes(2).unwrap_err();
+}
+
 macro_rules! make_map {
     ($($k:expr => $v:expr),+) => ({
         let mut map = HashMap::new();
@@ -446,6 +559,68 @@ async fn test_slice() {
     assert_eq!(vec!["a".to_owned(), "c".to_owned(), "d".to_owned()], rows);
 }
 
+#[tokio::test]
+async fn test_array_iter() {
+    #[derive(Clone)]
+    struct Miscounted(std::ops::Range<i32>);
+
+    impl Iterator for Miscounted {
+        type Item = i32;
+
+        fn next(&mut self) -> Option<i32> {
+            self.0.next()
+        }
+    }
+
+    impl ExactSizeIterator for Miscounted {
+        fn len(&self) -> usize {
+            self.0.len() + 1
+        }
+    }
+
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (
+                id SERIAL PRIMARY KEY,
+                f TEXT
+            );
+            INSERT INTO foo (f) VALUES ('a'), ('b'), ('c'), ('d');",
+        )
+        .await
+        .unwrap();
+
+    let stmt = client
+        .prepare("SELECT f FROM foo WHERE id = ANY($1) ORDER BY id")
+        .await
+        .unwrap();
+    let ids = [0i32, 2, 3];
+    let rows = client
+        .query(&stmt, &[&ArrayIter(ids.iter().map(|id| id + 1))])
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| r.get(0))
+        .collect::<Vec<String>>();
+    assert_eq!(vec!["a".to_owned(), "c".to_owned(), "d".to_owned()], rows);
+
+    let row = client
+        .query_one("SELECT $1::TEXT[]", &[&ArrayIter(["x", "y"].iter())])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, Vec<String>>(0), vec!["x", "y"]);
+
+    let err = client
+        .query(&stmt, &[&ArrayIter(Miscounted(1..3))])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        "expected 3 array elements but got 2"
+    );
+}
+
 #[tokio::test]
 async fn test_slice_wrong_type() {
     let client = connect("user=postgres").await;